@@ -0,0 +1,241 @@
+// analysis.rs
+// Análisis de un nivel: desde el inicio del jugador, ¿se llega a todos los
+// suministros y a la salida? ¿Cuánto hay que caminar? ¿Se escapa del mapa?
+
+use std::collections::VecDeque;
+use crate::maze::{Level, Maze};
+use crate::nav::Cell;
+use crate::tiles::{is_player_walkable, tile, Pickup};
+
+// Con más suministros el recorrido se aproxima yendo siempre al más cercano
+const EXACT_ROUTE_MAX_SUPPLIES: usize = 7;
+
+pub struct Reachability {
+    pub reachable_cells: usize,
+    pub supplies: Vec<Cell>,
+    pub unreachable_supplies: Vec<Cell>,
+    pub exit: Option<Cell>,
+    pub exit_reachable: bool,
+    // Pasos del inicio a la salida
+    pub exit_distance: Option<u32>,
+    // Recorrido más corto que junta todos los suministros y termina en la salida
+    pub route_length: Option<u32>,
+    // Celdas alcanzables con un vecino fuera de la grilla
+    pub leaks: Vec<Cell>,
+    // Level::grid_line, para informar líneas del archivo
+    pub grid_line: usize,
+}
+
+impl Reachability {
+    pub fn is_ok(&self) -> bool {
+        self.unreachable_supplies.is_empty() && self.exit_reachable && self.leaks.is_empty()
+    }
+}
+
+impl std::fmt::Display for Reachability {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "reachable cells: {}", self.reachable_cells)?;
+        writeln!(f, "supplies reachable: {}/{}", self.supplies.len() - self.unreachable_supplies.len(), self.supplies.len())?;
+        let line = |j: usize| self.grid_line + j + 1;
+        for &(i, j) in &self.unreachable_supplies {
+            writeln!(f, "  sealed off supply at line {}, column {}", line(j), i + 1)?;
+        }
+        match (self.exit, self.exit_distance) {
+            (None, _) => writeln!(f, "exit: none")?,
+            (Some(_), Some(d)) => writeln!(f, "exit: {} steps from the start", d)?,
+            (Some((i, j)), None) => writeln!(f, "exit: sealed off at line {}, column {}", line(j), i + 1)?,
+        }
+        if let Some(route) = self.route_length {
+            writeln!(f, "shortest route (all supplies, then exit): {} steps", route)?;
+        }
+        for &(i, j) in &self.leaks {
+            writeln!(f, "  leak outside the map at line {}, column {}", line(j), i + 1)?;
+        }
+        Ok(())
+    }
+}
+
+// Distancia BFS (para el jugador) desde `start` a cada celda; None = inalcanzable
+fn flood(maze: &Maze, start: Cell) -> Vec<Vec<Option<u32>>> {
+    let mut dist: Vec<Vec<Option<u32>>> = maze.iter().map(|row| vec![None; row.len()]).collect();
+    let walkable = |(i, j): Cell| maze.get(j).and_then(|row| row.get(i)).is_some_and(|&c| is_player_walkable(c));
+    if !walkable(start) {
+        return dist;
+    }
+
+    let mut queue = VecDeque::new();
+    dist[start.1][start.0] = Some(0);
+    queue.push_back(start);
+    while let Some((i, j)) = queue.pop_front() {
+        let d = dist[j][i].unwrap_or(0);
+        for (ni, nj) in [(i.wrapping_sub(1), j), (i + 1, j), (i, j.wrapping_sub(1)), (i, j + 1)] {
+            if walkable((ni, nj)) && dist[nj][ni].is_none() {
+                dist[nj][ni] = Some(d + 1);
+                queue.push_back((ni, nj));
+            }
+        }
+    }
+    dist
+}
+
+fn distance(dist: &[Vec<Option<u32>>], (i, j): Cell) -> Option<u32> {
+    dist.get(j).and_then(|row| row.get(i)).copied().flatten()
+}
+
+// Largo del mejor orden para visitar `stops` desde `start` y terminar en `exit`
+fn best_route(maze: &Maze, start: Cell, stops: &[Cell], exit: Cell) -> Option<u32> {
+    // Distancias entre todos los puntos: 0 = inicio, 1..=n = suministros
+    let points: Vec<Cell> = std::iter::once(start).chain(stops.iter().copied()).collect();
+    let floods: Vec<_> = points.iter().map(|&p| flood(maze, p)).collect();
+    let between = |a: usize, b: usize| distance(&floods[a], points[b]);
+    let to_exit = |a: usize| distance(&floods[a], exit);
+
+    let n = stops.len();
+    if n == 0 {
+        return to_exit(0);
+    }
+
+    if n <= EXACT_ROUTE_MAX_SUPPLIES {
+        let mut order: Vec<usize> = (1..=n).collect();
+        let mut best: Option<u32> = None;
+        permute(&mut order, 0, &mut |order| {
+            let mut total = 0;
+            let mut at = 0;
+            for &next in order {
+                total += between(at, next)?;
+                at = next;
+            }
+            let total = total + to_exit(at)?;
+            best = Some(best.map_or(total, |b| b.min(total)));
+            Some(())
+        });
+        best
+    } else {
+        let mut left: Vec<usize> = (1..=n).collect();
+        let mut total = 0;
+        let mut at = 0;
+        while !left.is_empty() {
+            let (k, d) = left.iter().enumerate()
+                .filter_map(|(k, &p)| between(at, p).map(|d| (k, d)))
+                .min_by_key(|&(_, d)| d)?;
+            total += d;
+            at = left.swap_remove(k);
+        }
+        Some(total + to_exit(at)?)
+    }
+}
+
+fn permute(items: &mut [usize], k: usize, visit: &mut impl FnMut(&[usize]) -> Option<()>) {
+    if k == items.len() {
+        visit(items);
+        return;
+    }
+    for i in k..items.len() {
+        items.swap(k, i);
+        permute(items, k + 1, visit);
+        items.swap(k, i);
+    }
+}
+
+/// `markers` son los caracteres de arquetipo que el llamador quita de la
+/// grilla, como en `validate_maze`: cuentan como pasillo.
+pub fn analyze(level: &Level, markers: &[char]) -> Reachability {
+    let maze: Maze = level.maze.iter()
+        .map(|row| row.iter().map(|&c| if markers.contains(&c) { ' ' } else { c }).collect())
+        .collect();
+    let maze = &maze;
+    let start = level.player_start;
+    let dist = flood(maze, start);
+
+    let mut supplies = Vec::new();
+    let mut exit = None;
+    let mut leaks = Vec::new();
+    let mut reachable_cells = 0;
+    for (j, row) in maze.iter().enumerate() {
+        for (i, &c) in row.iter().enumerate() {
+            match tile(c).pickup {
+                Some(Pickup::MedicalSupply) => supplies.push((i, j)),
+                Some(Pickup::Exit) => exit = exit.or(Some((i, j))),
+                None => {}
+            }
+            if dist[j][i].is_none() { continue; }
+            reachable_cells += 1;
+            // Un vecino que no existe en la grilla es una salida al vacío
+            let outside = i == 0 || j == 0
+                || i + 1 >= row.len()
+                || maze.get(j - 1).is_none_or(|r| i >= r.len())
+                || maze.get(j + 1).is_none_or(|r| i >= r.len());
+            if outside {
+                leaks.push((i, j));
+            }
+        }
+    }
+
+    let unreachable_supplies: Vec<Cell> = supplies.iter().copied().filter(|&s| distance(&dist, s).is_none()).collect();
+    let exit_distance = exit.and_then(|e| distance(&dist, e));
+    let route_length = match exit {
+        Some(e) if unreachable_supplies.is_empty() && exit_distance.is_some() => best_route(maze, start, &supplies, e),
+        _ => None,
+    };
+
+    Reachability {
+        reachable_cells,
+        supplies,
+        unreachable_supplies,
+        exit,
+        exit_reachable: exit_distance.is_some(),
+        exit_distance,
+        route_length,
+        leaks,
+        grid_line: level.grid_line,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::parse_level;
+
+    #[test]
+    fn finds_sealed_supplies_and_the_route() {
+        let level = parse_level("t", "+-----+\n|P M  |\n|---+-|\n|M| g |\n+-----+\n");
+        let report = analyze(&level, &[]);
+        assert_eq!(report.unreachable_supplies, vec![(1, 3)]);
+        assert!(!report.exit_reachable && !report.is_ok());
+
+        let level = parse_level("t", "+-----+\n|P M  |\n|--- -|\n|M  g |\n+-----+\n");
+        let report = analyze(&level, &[]);
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.exit_distance, Some(5));
+        // M de arriba (2), bajar hasta la M de abajo (6) y volver a la salida (3)
+        assert_eq!(report.route_length, Some(2 + 6 + 3));
+    }
+
+    #[test]
+    fn reports_cells_that_leak_outside() {
+        let level = parse_level("t", "+---+\n|P g\n+---+\n");
+        let report = analyze(&level, &[]);
+        assert_eq!(report.leaks, vec![(3, 1)]);
+
+        // Con cabecera la línea informada es la del archivo
+        let level = parse_level("t", "zombies = 1\n---\n+---+\n|P g\n+---+\n");
+        let report = analyze(&level, &[]).to_string();
+        assert!(report.contains("leak outside the map at line 4, column 4"), "{}", report);
+    }
+
+    #[test]
+    fn archetype_markers_count_as_floor() {
+        // Un zombie fijo en el pasillo no encierra la salida
+        let level = parse_level("t", "+-----+\n|P Z g|\n+-----+\n");
+        assert!(!analyze(&level, &[]).exit_reachable);
+        let report = analyze(&level, &['Z']);
+        assert!(report.is_ok(), "{}", report);
+        assert_eq!(report.exit_distance, Some(4));
+    }
+
+    #[test]
+    fn bundled_maze_is_fully_reachable() {
+        let report = analyze(&crate::maze::default_level(), &[]);
+        assert!(report.is_ok(), "{}", report);
+    }
+}
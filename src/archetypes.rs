@@ -0,0 +1,258 @@
+// archetypes.rs
+// Tipos de zombie definidos en un archivo de texto (ver zombies.txt).
+
+use rand::Rng;
+use std::fs;
+use crate::maze::Maze;
+use crate::nav::Cell;
+
+// Copia incluida en el binario, por si falta o falla el archivo
+const BUILTIN: &str = include_str!("../zombies.txt");
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Archetype {
+    pub name: String,
+    pub speed: f32,
+    pub radius: f32,
+    pub view_distance: f32,
+    // Medio ángulo del cono de visión, en radianes
+    pub view_half_angle: f32,
+    pub hearing: f32,
+    pub sprite: char,
+    pub scale: f32,
+    // 0 = no alerta a nadie
+    pub alert_radius: f32,
+    pub sound: Option<String>,
+    pub weight: u32,
+    pub marker: Option<char>,
+}
+
+impl Default for Archetype {
+    fn default() -> Self {
+        Archetype {
+            name: "shambler".to_string(),
+            speed: 75.0,
+            radius: 18.0,
+            view_distance: 640.0,
+            view_half_angle: 60f32.to_radians(),
+            hearing: 1.0,
+            sprite: 'z',
+            scale: 1.0,
+            alert_radius: 0.0,
+            sound: None,
+            weight: 1,
+            marker: None,
+        }
+    }
+}
+
+fn parse_char(value: &str) -> Option<char> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
+/// Lee secciones `[nombre]` con líneas `clave = valor`. Los errores indican
+/// el número de línea. El radio tiene que ser menor que media celda de
+/// `block_size`: resolve_overlaps solo mira las celdas vecinas.
+pub fn parse_archetypes(text: &str, block_size: usize) -> Result<Vec<Archetype>, String> {
+    let mut list: Vec<Archetype> = Vec::new();
+
+    for (n, raw) in text.lines().enumerate() {
+        let line_no = n + 1;
+        let line = raw.split('#').next().unwrap_or("").trim();
+        if line.is_empty() { continue; }
+
+        if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+            let name = name.trim();
+            if name.is_empty() {
+                return Err(format!("line {line_no}: empty archetype name"));
+            }
+            if list.iter().any(|a| a.name == name) {
+                return Err(format!("line {line_no}: duplicate archetype '{name}'"));
+            }
+            list.push(Archetype { name: name.to_string(), ..Archetype::default() });
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(format!("line {line_no}: expected 'key = value', got '{line}'"));
+        };
+        let (key, value) = (key.trim(), value.trim());
+        let Some(arch) = list.last_mut() else {
+            return Err(format!("line {line_no}: '{key}' outside of an [archetype] section"));
+        };
+
+        let number = || value.parse::<f32>()
+            .ok()
+            .filter(|v| v.is_finite() && *v >= 0.0)
+            .ok_or_else(|| format!("line {line_no}: '{key}' needs a non-negative number, got '{value}'"));
+        let positive = |max: f32| number()
+            .ok()
+            .filter(|v| *v > 0.0 && *v < max)
+            .ok_or_else(|| if max.is_finite() {
+                format!("line {line_no}: '{key}' needs a number above 0 and below {max}, got '{value}'")
+            } else {
+                format!("line {line_no}: '{key}' needs a number above 0, got '{value}'")
+            });
+        let integer = || value.parse::<u32>()
+            .map_err(|_| format!("line {line_no}: '{key}' needs a whole number, got '{value}'"));
+        let character = || parse_char(value)
+            .ok_or_else(|| format!("line {line_no}: '{key}' needs a single character, got '{value}'"));
+
+        match key {
+            "speed" => arch.speed = positive(f32::INFINITY)?,
+            "radius" => arch.radius = positive(block_size as f32 / 2.0)?,
+            "view_distance" => arch.view_distance = number()?,
+            "view_angle" => arch.view_half_angle = (number()? / 2.0).to_radians(),
+            "hearing" => arch.hearing = number()?,
+            "sprite" => arch.sprite = character()?,
+            "scale" => arch.scale = number()?,
+            "alert_radius" => arch.alert_radius = number()?,
+            "sound" => arch.sound = Some(value.to_string()),
+            "weight" => arch.weight = integer()?,
+            "marker" => arch.marker = Some(character()?),
+            _ => return Err(format!("line {line_no}: unknown key '{key}'")),
+        }
+    }
+
+    if list.is_empty() {
+        return Err("no archetypes defined".to_string());
+    }
+    Ok(list)
+}
+
+pub fn load_archetypes(filename: &str, block_size: usize) -> Vec<Archetype> {
+    let parsed = match fs::read_to_string(filename) {
+        Ok(text) => parse_archetypes(&text, block_size).map_err(|e| format!("ERROR in '{}': {}", filename, e)),
+        Err(e) => Err(format!("WARNING: could not read '{}': {}", filename, e)),
+    };
+    match parsed {
+        Ok(list) => {
+            println!("Loaded {} zombie archetypes from '{}'", list.len(), filename);
+            list
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            eprintln!("Using built-in zombie archetypes");
+            builtin_archetypes(block_size)
+        }
+    }
+}
+
+pub fn builtin_archetypes(block_size: usize) -> Vec<Archetype> {
+    parse_archetypes(BUILTIN, block_size).unwrap_or_else(|_| vec![Archetype::default()])
+}
+
+/// Elige un arquetipo al azar según `weight`.
+pub fn pick_archetype<'a>(list: &'a [Archetype], rng: &mut impl Rng) -> Option<&'a Archetype> {
+    let total: u32 = list.iter().map(|a| a.weight).sum();
+    if total == 0 {
+        return list.first();
+    }
+    let mut roll = rng.gen_range(0..total);
+    for arch in list {
+        if roll < arch.weight {
+            return Some(arch);
+        }
+        roll -= arch.weight;
+    }
+    list.last()
+}
+
+pub fn archetype_for_marker(list: &[Archetype], c: char) -> Option<&Archetype> {
+    list.iter().find(|a| a.marker == Some(c))
+}
+
+/// Saca del laberinto los marcadores de arquetipo (quedan como pasillo) y
+/// devuelve dónde va cada uno, como índice en `list`.
+pub fn take_placements(maze: &mut Maze, list: &[Archetype]) -> Vec<(Cell, usize)> {
+    let mut placements = Vec::new();
+    for (j, row) in maze.iter_mut().enumerate() {
+        for (i, c) in row.iter_mut().enumerate() {
+            if let Some(idx) = list.iter().position(|a| a.marker == Some(*c)) {
+                placements.push(((i, j), idx));
+                *c = ' ';
+            }
+        }
+    }
+    placements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const BS: usize = 80;
+
+    #[test]
+    fn bundled_file_parses() {
+        let list = parse_archetypes(BUILTIN, BS).unwrap();
+        let crawler = list.iter().find(|a| a.name == "crawler").unwrap();
+        assert_eq!(crawler.radius, 10.0);
+        assert!((crawler.view_half_angle - 45f32.to_radians()).abs() < 1e-6);
+        assert_eq!(archetype_for_marker(&list, 'S').map(|a| a.name.as_str()), Some("screamer"));
+        // Los sonidos que nombra el archivo vienen con el repo
+        for sound in list.iter().filter_map(|a| a.sound.as_deref()) {
+            assert!(std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(sound).exists(), "missing {sound}");
+        }
+    }
+
+    #[test]
+    fn missing_keys_use_defaults_and_errors_report_the_line() {
+        let list = parse_archetypes("[slow]\nspeed = 10 # muy lento\n", BS).unwrap();
+        assert_eq!(list[0].speed, 10.0);
+        assert_eq!(list[0].radius, Archetype::default().radius);
+
+        assert_eq!(parse_archetypes("speed = 3", BS).unwrap_err(), "line 1: 'speed' outside of an [archetype] section");
+        assert!(parse_archetypes("[a]\n\nspeed = fast", BS).unwrap_err().starts_with("line 3:"));
+        assert!(parse_archetypes("[a]\nmarker = ZZ", BS).is_err());
+    }
+
+    #[test]
+    fn speed_radius_and_weight_are_range_checked() {
+        assert_eq!(
+            parse_archetypes("[a]\nspeed = 0", BS).unwrap_err(),
+            "line 2: 'speed' needs a number above 0, got '0'"
+        );
+        assert_eq!(
+            parse_archetypes("[a]\nradius = 0", BS).unwrap_err(),
+            "line 2: 'radius' needs a number above 0 and below 40, got '0'"
+        );
+        assert_eq!(
+            parse_archetypes("[a]\n\nradius = 40", BS).unwrap_err(),
+            "line 3: 'radius' needs a number above 0 and below 40, got '40'"
+        );
+        assert_eq!(
+            parse_archetypes("[a]\nweight = 1.5", BS).unwrap_err(),
+            "line 2: 'weight' needs a whole number, got '1.5'"
+        );
+        assert!(parse_archetypes("[a]\nweight = -1", BS).unwrap_err().starts_with("line 2:"));
+        // El límite del radio depende del tamaño de celda
+        assert_eq!(parse_archetypes("[a]\nradius = 39.5", BS).unwrap()[0].radius, 39.5);
+        assert!(parse_archetypes("[a]\nradius = 39.5", 64).is_err());
+    }
+
+    #[test]
+    fn markers_become_floor_and_placements() {
+        let list = builtin_archetypes(BS);
+        let mut maze: Maze = ["#####", "#R K#", "#####"].iter().map(|r| r.chars().collect()).collect();
+        let placed = take_placements(&mut maze, &list);
+        let names: Vec<(Cell, &str)> = placed.iter().map(|&(c, idx)| (c, list[idx].name.as_str())).collect();
+        assert_eq!(names, vec![((1, 1), "runner"), ((3, 1), "crawler")]);
+        assert_eq!(maze[1], vec!['#', ' ', ' ', ' ', '#']);
+    }
+
+    #[test]
+    fn pick_respects_weights() {
+        let list = parse_archetypes("[never]\nweight = 0\n[always]\nweight = 3\n", BS).unwrap();
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..20 {
+            assert_eq!(pick_archetype(&list, &mut rng).unwrap().name, "always");
+        }
+    }
+}
@@ -2,6 +2,9 @@
 use raylib::prelude::*; 
 use raylib::ffi as rlffi;
 use std::ffi::CString;
+use std::collections::HashMap;
+
+const DEFAULT_MUSIC: &str = "sounds/background_music.mp3";
 
 pub struct AudioManager {
     audio_ready: bool,
@@ -9,6 +12,8 @@ pub struct AudioManager {
     pickup: Option<rlffi::Sound>,     
     victory: Option<rlffi::Sound>,    
     ui_jingle: Option<rlffi::Sound>,  
+    // Sonidos de los arquetipos de zombie, cargados la primera vez que suenan
+    zombie_sounds: HashMap<String, Option<rlffi::Sound>>,
     muted: bool,
 }
 
@@ -20,17 +25,7 @@ impl AudioManager {
             return Err("Audio device not ready".into());
         }
 
-        let music = unsafe {
-            let cpath = CString::new("sounds/background_music.mp3").unwrap();
-            let m = rlffi::LoadMusicStream(cpath.as_ptr());
-            if m.ctxData.is_null() {
-                eprintln!("[audio] Could not load sounds/background_music.mp3");
-                None
-            } else {
-                rlffi::SetMusicVolume(m, 0.65);
-                Some(m)
-            }
-        };
+        let music = Self::load_music(DEFAULT_MUSIC);
 
         let pickup = unsafe {
             let cpath = CString::new("sounds/medical_pickup.wav").unwrap();
@@ -82,10 +77,37 @@ impl AudioManager {
             pickup,
             victory,
             ui_jingle,   
+            zombie_sounds: HashMap::new(),
             muted: false,
         })
     }
 
+    fn load_music(path: &str) -> Option<rlffi::Music> {
+        unsafe {
+            let cpath = CString::new(path).ok()?;
+            let m = rlffi::LoadMusicStream(cpath.as_ptr());
+            if m.ctxData.is_null() {
+                eprintln!("[audio] Could not load {}", path);
+                None
+            } else {
+                rlffi::SetMusicVolume(m, 0.65);
+                Some(m)
+            }
+        }
+    }
+
+    // Música propia del nivel; `None` vuelve a la de siempre
+    pub fn set_level_music(&mut self, _rl: &mut RaylibHandle, path: Option<&str>) {
+        if !self.audio_ready { return; }
+        let Some(music) = Self::load_music(path.unwrap_or(DEFAULT_MUSIC)) else { return; };
+        if let Some(old) = self.music.replace(music) {
+            unsafe {
+                rlffi::StopMusicStream(old);
+                rlffi::UnloadMusicStream(old);
+            }
+        }
+    }
+
     pub fn update(&mut self, _rl: &mut RaylibHandle) {
         if !self.audio_ready { return; }
         if let Some(m) = &mut self.music {
@@ -136,6 +158,23 @@ impl AudioManager {
         }
     }
 
+    pub fn play_zombie_sound(&mut self, _rl: &mut RaylibHandle, path: &str) {
+        if !self.audio_ready || self.muted { return; }
+        let sound = self.zombie_sounds.entry(path.to_string()).or_insert_with(|| unsafe {
+            let cpath = CString::new(path).ok()?;
+            let s = rlffi::LoadSound(cpath.as_ptr());
+            if s.frameCount == 0 {
+                eprintln!("[audio] Could not load {}", path);
+                None
+            } else {
+                Some(s)
+            }
+        });
+        if let Some(s) = sound {
+            unsafe { rlffi::PlaySound(*s); }
+        }
+    }
+
     pub fn is_music_playing(&self) -> bool {
         if !self.audio_ready { return false; }
         if let Some(m) = &self.music {
@@ -1,16 +1,127 @@
 // caster.rs
 use raylib::color::Color;
+use raylib::prelude::Vector2;
 use crate::framebuffer::Framebuffer;
+use crate::line::line;
 use crate::maze::Maze;
 use crate::player::Player;
+use crate::tiles::blocks_sight;
+
+// Cara de la celda golpeada por el rayo (y crece hacia abajo)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WallFace {
+  North,
+  South,
+  East,
+  West,
+}
+
+impl WallFace {
+  /// Normal hacia afuera de la cara, en coordenadas del mundo.
+  pub fn normal(self) -> Vector2 {
+    match self {
+      WallFace::North => Vector2::new(0.0, -1.0),
+      WallFace::South => Vector2::new(0.0, 1.0),
+      WallFace::East => Vector2::new(1.0, 0.0),
+      WallFace::West => Vector2::new(-1.0, 0.0),
+    }
+  }
+}
 
 pub struct Intersect {
   pub distance: f32,
   pub impact: char,
   pub hit_x: f32,
   pub hit_y: f32,
+  pub cell: (usize, usize),
+  pub face: WallFace,
+  pub normal: Vector2,
+  // Coordenada U de textura en [0, 1), de izquierda a derecha vista desde afuera de la cara
+  pub tex_u: f32,
+}
+
+// Celdas que el rayo atraviesa; las de sprites se dibujan aparte
+pub fn is_see_through(c: char) -> bool {
+  !blocks_sight(c)
 }
 
+// Primer muro que cruza un rayo, en unidades de celda
+struct Hit {
+  t: f32,
+  map_i: i64,
+  map_j: i64,
+  face: WallFace,
+  impact: char,
+}
+
+// Recorre la grilla celda por celda (DDA) desde `(px, py)`, en unidades de
+// celda, hasta el primer muro. Fuera del mapa todo cuenta como '#'.
+fn march(maze: &Maze, px: f32, py: f32, dir_x: f32, dir_y: f32) -> Hit {
+  let mut map_i = px.floor() as i64;
+  let mut map_j = py.floor() as i64;
+
+  let delta_x = if dir_x == 0.0 { f32::INFINITY } else { (1.0 / dir_x).abs() };
+  let delta_y = if dir_y == 0.0 { f32::INFINITY } else { (1.0 / dir_y).abs() };
+
+  let (step_i, mut side_x) = if dir_x < 0.0 {
+    (-1, (px - map_i as f32) * delta_x)
+  } else {
+    (1, (map_i as f32 + 1.0 - px) * delta_x)
+  };
+  let (step_j, mut side_y) = if dir_y < 0.0 {
+    (-1, (py - map_j as f32) * delta_y)
+  } else {
+    (1, (map_j as f32 + 1.0 - py) * delta_y)
+  };
+
+  loop {
+    // Avanza hacia la frontera de celda más cercana
+    let (t, face) = if side_x < side_y {
+      let t = side_x;
+      side_x += delta_x;
+      map_i += step_i;
+      (t, if step_i > 0 { WallFace::West } else { WallFace::East })
+    } else {
+      let t = side_y;
+      side_y += delta_y;
+      map_j += step_j;
+      (t, if step_j > 0 { WallFace::North } else { WallFace::South })
+    };
+
+    let impact = if map_j < 0 || map_i < 0 {
+      Some('#')
+    } else {
+      match maze.get(map_j as usize) {
+        None => Some('#'),
+        Some(row) => match row.get(map_i as usize) {
+          None => Some('#'),
+          Some(&c) if is_see_through(c) => None,
+          Some(&c) => Some(c),
+        },
+      }
+    };
+
+    if let Some(impact) = impact {
+      return Hit { t, map_i, map_j, face, impact };
+    }
+  }
+}
+
+/// `true` si ningún muro corta el segmento entre `from` y `to`.
+pub fn line_of_sight(maze: &Maze, from: Vector2, to: Vector2, block_size: usize) -> bool {
+  let bs = block_size as f32;
+  let (dx, dy) = (to.x - from.x, to.y - from.y);
+  let dist = (dx * dx + dy * dy).sqrt();
+  if dist < 0.001 {
+    return true;
+  }
+  let hit = march(maze, from.x / bs, from.y / bs, dx / dist, dy / dist);
+  hit.t * bs >= dist
+}
+
+/// Recorre la grilla celda por celda (DDA) y devuelve el primer muro que
+/// cruza el rayo. `distance` es la distancia euclidiana exacta al punto de
+/// impacto; las celdas fuera del mapa cuentan como muro sólido (`'#'`).
 pub fn cast_ray(
   framebuffer: &mut Framebuffer,
   maze: &Maze,
@@ -19,42 +130,43 @@ pub fn cast_ray(
   block_size: usize,
   draw_line: bool,
 ) -> Intersect {
-  let mut d = 1.0;
-  framebuffer.set_current_color(Color::WHITESMOKE);
+  let bs = block_size as f32;
+  let (dir_x, dir_y) = (a.cos(), a.sin());
 
-  loop {
-    let cos = d * a.cos();
-    let sin = d * a.sin();
-    let xf = player.pos.x + cos;
-    let yf = player.pos.y + sin;
-    let x = xf as usize;
-    let y = yf as usize;
-
-    let i = x / block_size;
-    let j = y / block_size;
-
-    if j >= maze.len() || i >= maze[j].len() {
-      return Intersect{
-        distance: d,
-        impact: '#',
-        hit_x: player.pos.x + d * a.cos(),   
-        hit_y: player.pos.y + d * a.sin(),   
-      };
-    }
+  // Posición en unidades de celda
+  let px = player.pos.x / bs;
+  let py = player.pos.y / bs;
+  let Hit { t, map_i, map_j, face, impact } = march(maze, px, py, dir_x, dir_y);
 
-    if maze[j][i] != ' ' {
-      return Intersect{
-        distance: d,
-        impact: maze[j][i],
-        hit_x: player.pos.x + d * a.cos(),   
-        hit_y: player.pos.y + d * a.sin(),   
-      };
-    }
+  let distance = t * bs;
+  let hit_x = player.pos.x + distance * dir_x;
+  let hit_y = player.pos.y + distance * dir_y;
 
-    if draw_line {
-      framebuffer.set_pixel(x as u32, y as u32);
-    }
+  // Fracción a lo largo de la cara; se invierte en East/North para que
+  // la textura no quede espejada y sea continua entre celdas vecinas.
+  let along = match face {
+    WallFace::East | WallFace::West => py + t * dir_y,
+    WallFace::North | WallFace::South => px + t * dir_x,
+  };
+  let frac = along - along.floor();
+  let tex_u = match face {
+    WallFace::West | WallFace::South => frac,
+    WallFace::East | WallFace::North => 1.0 - frac,
+  }.clamp(0.0, 0.9999);
+
+  if draw_line {
+    framebuffer.set_current_color(Color::WHITESMOKE);
+    line(framebuffer, player.pos, Vector2::new(hit_x, hit_y));
+  }
 
-    d += 1.0;
+  Intersect {
+    distance,
+    impact,
+    hit_x,
+    hit_y,
+    cell: (map_i.max(0) as usize, map_j.max(0) as usize),
+    face,
+    normal: face.normal(),
+    tex_u,
   }
 }
@@ -1,11 +1,22 @@
-// framebuffer.rs 
+// framebuffer.rs
 
 use raylib::prelude::*;
 
+// Un píxel RGBA; el buffer completo se puede subir tal cual como R8G8B8A8
+pub type Pixel = [u8; 4];
+
+fn to_pixel(color: Color) -> Pixel {
+    [color.r, color.g, color.b, color.a]
+}
+
+fn to_color(pixel: Pixel) -> Color {
+    Color::new(pixel[0], pixel[1], pixel[2], pixel[3])
+}
+
 pub struct Framebuffer {
     pub width: u32,
     pub height: u32,
-    pub color_buffer: Image,
+    pixels: Vec<Pixel>,
     background_color: Color,
     current_color: Color,
     texture: Option<Texture2D>,
@@ -13,11 +24,10 @@ pub struct Framebuffer {
 
 impl Framebuffer {
     pub fn new(width: u32, height: u32) -> Self {
-        let color_buffer = Image::gen_image_color(width as i32, height as i32, Color::BLACK);
         Framebuffer {
             width,
             height,
-            color_buffer,
+            pixels: vec![to_pixel(Color::BLACK); (width * height) as usize],
             background_color: Color::BLACK,
             current_color: Color::WHITE,
             texture: None,
@@ -25,15 +35,47 @@ impl Framebuffer {
     }
 
     pub fn clear(&mut self) {
-        self.color_buffer = Image::gen_image_color(self.width as i32, self.height as i32, self.background_color);
+        self.pixels.fill(to_pixel(self.background_color));
     }
 
     pub fn set_pixel(&mut self, x: u32, y: u32) {
+        self.set_pixel_color(x, y, self.current_color);
+    }
+
+    pub fn set_pixel_color(&mut self, x: u32, y: u32, color: Color) {
+        if x < self.width && y < self.height {
+            self.pixels[(y * self.width + x) as usize] = to_pixel(color);
+        }
+    }
+
+    // Fuera del buffer devuelve None, igual que set_pixel ignora esas escrituras
+    pub fn get_pixel(&self, x: u32, y: u32) -> Option<Color> {
         if x < self.width && y < self.height {
-            self.color_buffer.draw_pixel(x as i32, y as i32, self.current_color);
+            Some(to_color(self.pixels[(y * self.width + x) as usize]))
+        } else {
+            None
         }
     }
 
+    pub fn pixels(&self) -> &[Pixel] {
+        &self.pixels
+    }
+
+    pub fn row(&self, y: u32) -> &[Pixel] {
+        let start = (y * self.width) as usize;
+        &self.pixels[start..start + self.width as usize]
+    }
+
+    pub fn row_mut(&mut self, y: u32) -> &mut [Pixel] {
+        let start = (y * self.width) as usize;
+        &mut self.pixels[start..start + self.width as usize]
+    }
+
+    // Bytes RGBA contiguos, fila por fila
+    pub fn as_bytes(&self) -> &[u8] {
+        self.pixels.as_flattened()
+    }
+
     pub fn set_background_color(&mut self, color: Color) {
         self.background_color = color;
     }
@@ -42,8 +84,49 @@ impl Framebuffer {
         self.current_color = color;
     }
 
-    pub fn _render_to_file(&self, file_path: &str) {
-        self.color_buffer.export_image(file_path);
+    // Copia a una Image de raylib (solo al presentar o exportar)
+    pub fn to_image(&self) -> Image {
+        // gen_image_color reserva un buffer R8G8B8A8 del mismo tamaño; se copia entero
+        let image = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+        let bytes = self.as_bytes();
+        assert_eq!(image.get_pixel_data_size(), bytes.len());
+        unsafe {
+            std::ptr::copy_nonoverlapping(bytes.as_ptr(), image.data() as *mut u8, bytes.len());
+        }
+        image
+    }
+
+    pub fn render_to_file(&self, file_path: &str) {
+        self.to_image().export_image(file_path);
+    }
+
+    // Cambia el tamaño del buffer; la textura se recrea en la próxima subida
+    pub fn resize(&mut self, width: u32, height: u32) {
+        if width == self.width && height == self.height {
+            return;
+        }
+        self.width = width;
+        self.height = height;
+        self.pixels = vec![to_pixel(self.background_color); (width * height) as usize];
+    }
+
+    // Crea la textura la primera vez (o si cambió el tamaño) y después solo
+    // actualiza sus píxeles en la GPU
+    fn upload_texture(&mut self, window: &mut RaylibHandle, raylib_thread: &RaylibThread) {
+        let stale = match &self.texture {
+            Some(texture) => texture.width() != self.width as i32 || texture.height() != self.height as i32,
+            None => true,
+        };
+        if stale {
+            let blank = Image::gen_image_color(self.width as i32, self.height as i32, Color::BLACK);
+            self.texture = window.load_texture_from_image(raylib_thread, &blank).ok();
+        }
+
+        if let Some(texture) = &mut self.texture {
+            if let Err(e) = texture.update_texture(self.pixels.as_flattened()) {
+                eprintln!("[framebuffer] texture update failed: {}", e);
+            }
+        }
     }
 
     // Simple rendering method for 2D mode
@@ -52,19 +135,7 @@ impl Framebuffer {
         window: &mut RaylibHandle,
         raylib_thread: &RaylibThread,
     ) {
-        // Create or update texture
-        match &mut self.texture {
-            Some(_texture) => {
-                if let Ok(new_texture) = window.load_texture_from_image(raylib_thread, &self.color_buffer) {
-                    self.texture = Some(new_texture);
-                }
-            },
-            None => {
-                if let Ok(new_texture) = window.load_texture_from_image(raylib_thread, &self.color_buffer) {
-                    self.texture = Some(new_texture);
-                }
-            }
-        }
+        self.upload_texture(window, raylib_thread);
 
         if let Some(ref texture) = self.texture {
             let mut renderer = window.begin_drawing(raylib_thread);
@@ -72,55 +143,112 @@ impl Framebuffer {
         }
     }
 
+    // Dibuja el buffer dentro de un frame ya abierto (para poner el HUD encima)
+    pub fn render_into(
+        &mut self,
+        d: &mut RaylibDrawHandle,
+        raylib_thread: &RaylibThread,
+    ) {
+        self.upload_texture(d, raylib_thread);
+
+        if let Some(ref texture) = self.texture {
+            d.draw_texture(texture, 0, 0, Color::WHITE);
+        }
+    }
+
     pub fn render_to_screen_with_fps(
         &mut self,
         window: &mut RaylibHandle,
         raylib_thread: &RaylibThread,
         fps: u32,
     ) {
-        match &mut self.texture {
-            Some(_texture) => {
-                if let Ok(new_texture) = window.load_texture_from_image(raylib_thread, &self.color_buffer) {
-                    self.texture = Some(new_texture);
-                }
-            },
-            None => {
-                if let Ok(new_texture) = window.load_texture_from_image(raylib_thread, &self.color_buffer) {
-                    self.texture = Some(new_texture);
-                }
-            }
-        }
+        self.upload_texture(window, raylib_thread);
 
         // Draw everything
         if let Some(ref texture) = self.texture {
             let mut renderer = window.begin_drawing(raylib_thread);
-            
+
             renderer.draw_texture(texture, 0, 0, Color::WHITE);
-            
+
             let fps_text = format!("FPS: {}", fps);
             let fps_color = if fps >= 30 { Color::GREEN } else if fps >= 15 { Color::YELLOW } else { Color::RED };
-            
+
             renderer.draw_rectangle(10, 10, 120, 25, Color::new(0, 0, 0, 180));
-            
+
             renderer.draw_text(&fps_text, 15, 15, 20, fps_color);
         }
     }
 
     pub fn fill_rect(&mut self, x: u32, y: u32, width: u32, height: u32) {
-        for py in y..(y + height) {
-            for px in x..(x + width) {
-                if px < self.width && py < self.height {
-                    self.color_buffer.draw_pixel(px as i32, py as i32, self.current_color);
-                }
+        let pixel = to_pixel(self.current_color);
+        let x1 = (x + width).min(self.width);
+        for py in y..(y + height).min(self.height) {
+            if x < x1 {
+                self.row_mut(py)[x as usize..x1 as usize].fill(pixel);
             }
         }
     }
 
     pub fn draw_line(&mut self, start_x: u32, start_y: u32, end_x: u32, end_y: u32) {
-        self.color_buffer.draw_line(start_x as i32, start_y as i32, end_x as i32, end_y as i32, self.current_color);
+        crate::line::line(
+            self,
+            Vector2::new(start_x as f32, start_y as f32),
+            Vector2::new(end_x as f32, end_y as f32),
+        );
     }
 
     pub fn draw_circle(&mut self, center_x: u32, center_y: u32, radius: u32) {
-        self.color_buffer.draw_circle(center_x as i32, center_y as i32, radius as i32, self.current_color);
+        let (cx, cy, r) = (center_x as i64, center_y as i64, radius as i64);
+        for py in (cy - r).max(0)..=(cy + r) {
+            for px in (cx - r).max(0)..=(cx + r) {
+                let (dx, dy) = (px - cx, py - cy);
+                if dx * dx + dy * dy <= r * r {
+                    self.set_pixel(px as u32, py as u32);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn clear_fills_in_place_with_background() {
+        let mut fb = Framebuffer::new(4, 3);
+        fb.set_background_color(Color::new(10, 20, 30, 255));
+        fb.clear();
+        assert!(fb.pixels().iter().all(|p| *p == [10, 20, 30, 255]));
+        assert_eq!(fb.as_bytes().len(), 4 * 3 * 4);
+    }
+
+    #[test]
+    fn writes_outside_the_buffer_are_ignored() {
+        let mut fb = Framebuffer::new(4, 3);
+        fb.set_current_color(Color::WHITE);
+        fb.set_pixel(4, 0);
+        fb.set_pixel(0, 3);
+        fb.fill_rect(2, 1, 10, 10);
+        assert_eq!(fb.row(0), &[[0, 0, 0, 255]; 4]);
+        assert_eq!(fb.row(1), &[[0, 0, 0, 255], [0, 0, 0, 255], [255; 4], [255; 4]]);
+        assert_eq!(fb.get_pixel(3, 2).map(|c| c.r), Some(255));
+        assert!(fb.get_pixel(4, 0).is_none());
+        assert!(fb.get_pixel(0, 3).is_none());
+    }
+
+    #[test]
+    fn to_image_copies_every_pixel() {
+        let mut fb = Framebuffer::new(3, 2);
+        fb.set_pixel_color(0, 0, Color::new(1, 2, 3, 4));
+        fb.set_pixel_color(2, 1, Color::new(200, 100, 50, 255));
+        let mut image = fb.to_image();
+        assert_eq!((image.width(), image.height()), (3, 2));
+        let c = image.get_color(0, 0);
+        assert_eq!([c.r, c.g, c.b, c.a], [1, 2, 3, 4]);
+        let c = image.get_color(2, 1);
+        assert_eq!([c.r, c.g, c.b, c.a], [200, 100, 50, 255]);
+        let c = image.get_color(1, 0);
+        assert_eq!([c.r, c.g, c.b, c.a], [0, 0, 0, 255]);
     }
-}
\ No newline at end of file
+}
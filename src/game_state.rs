@@ -7,6 +7,9 @@ pub enum GameState {
     Playing,
     Victory,
     Defeat, 
+    Paused,
+    // Pantalla de controles; vuelve al estado desde el que se abrió
+    Controls,
 }
 
 pub struct GameData {
@@ -16,6 +19,7 @@ pub struct GameData {
     pub victory_sound_played: bool,
     pub start_time: Instant,        
     pub time_limit: Duration,       
+    paused_at: Option<Instant>,
 }
 
 impl GameData {
@@ -27,6 +31,7 @@ impl GameData {
             victory_sound_played: false,
             start_time: Instant::now(),
             time_limit: Duration::from_secs(5 * 60), 
+            paused_at: None,
         }
     }
 
@@ -43,10 +48,25 @@ impl GameData {
         self.game_state = GameState::Playing;
         self.victory_sound_played = false;
         self.start_time = Instant::now(); 
+        self.paused_at = None;
+    }
+
+    // El reloj no corre mientras el juego está en pausa
+    pub fn pause(&mut self) {
+        self.paused_at.get_or_insert_with(Instant::now);
+        self.game_state = GameState::Paused;
+    }
+
+    pub fn resume(&mut self) {
+        if let Some(t) = self.paused_at.take() {
+            self.start_time += t.elapsed();
+        }
+        self.game_state = GameState::Playing;
     }
 
     pub fn time_remaining(&self) -> Duration {
-        let elapsed = self.start_time.elapsed();
+        let now = self.paused_at.unwrap_or_else(Instant::now);
+        let elapsed = now.duration_since(self.start_time);
         if elapsed >= self.time_limit { Duration::from_secs(0) } else { self.time_limit - elapsed }
     }
 }
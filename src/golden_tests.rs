@@ -0,0 +1,152 @@
+// golden_tests.rs
+// Renderiza poses fijas sin ventana y las compara con imágenes de referencia
+// en tests/golden/. Para regenerarlas: UPDATE_GOLDEN=1 cargo test
+
+use raylib::prelude::*;
+use std::f32::consts::PI;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::framebuffer::Framebuffer;
+use crate::maze::{load_floor_map, load_maze, Maze};
+use crate::player::Player;
+use crate::renderer::{render_world, Scene};
+use crate::sprites::{maze_sprites, Sprite, SpriteImage};
+use crate::texture::TextureManager;
+use crate::zombie::Zombie;
+
+const WIDTH: u32 = 96;
+const HEIGHT: u32 = 64;
+const BLOCK_SIZE: usize = 80;
+
+// Diferencia máxima por canal y fracción de píxeles que puede superarla
+const CHANNEL_TOLERANCE: u8 = 8;
+const MAX_BAD_PIXELS: f32 = 0.005;
+
+fn golden_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden")
+}
+
+fn render_pose(pos: Vector2, a: f32, extra_sprites: Vec<Sprite>, floor_map: Option<&Maze>) -> Vec<u8> {
+    let maze = load_maze(golden_dir().join("maze.txt").to_str().unwrap()).unwrap();
+    let textures = TextureManager::procedural();
+    let player = Player::new(pos, a, PI / 3.0);
+
+    let mut sprites = maze_sprites(&maze, BLOCK_SIZE, None);
+    sprites.extend(extra_sprites);
+
+    let mut framebuffer = Framebuffer::new(WIDTH, HEIGHT);
+    let mut zbuffer = Vec::new();
+    render_world(&mut framebuffer, &mut zbuffer, &Scene {
+        maze: &maze,
+        floor_map,
+        block_size: BLOCK_SIZE,
+        player: &player,
+        sprites: &sprites,
+        textures: &textures,
+    });
+
+    let mut rgb = Vec::with_capacity((WIDTH * HEIGHT * 3) as usize);
+    for pixel in framebuffer.pixels() {
+        rgb.extend_from_slice(&pixel[..3]);
+    }
+    rgb
+}
+
+fn write_ppm(path: &Path, rgb: &[u8]) {
+    let mut data = format!("P6\n{} {}\n255\n", WIDTH, HEIGHT).into_bytes();
+    data.extend_from_slice(rgb);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, data).unwrap();
+}
+
+fn read_ppm(path: &Path) -> Option<(u32, u32, Vec<u8>)> {
+    let data = fs::read(path).ok()?;
+    // Cabecera: "P6", ancho, alto y valor máximo separados por espacios
+    let mut fields = Vec::new();
+    let mut pos = 0;
+    while fields.len() < 4 {
+        while data.get(pos)?.is_ascii_whitespace() { pos += 1; }
+        let start = pos;
+        while !data.get(pos)?.is_ascii_whitespace() { pos += 1; }
+        fields.push(std::str::from_utf8(&data[start..pos]).ok()?.to_string());
+    }
+    if fields[0] != "P6" || fields[3] != "255" { return None; }
+    let w = fields[1].parse().ok()?;
+    let h = fields[2].parse().ok()?;
+    Some((w, h, data[pos + 1..].to_vec()))
+}
+
+fn check_golden(name: &str, pos: Vector2, a: f32, extra_sprites: Vec<Sprite>, floor_map: Option<&Maze>) {
+    let actual = render_pose(pos, a, extra_sprites, floor_map);
+    let path = golden_dir().join(format!("{name}.ppm"));
+
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        write_ppm(&path, &actual);
+        return;
+    }
+
+    let (w, h, expected) = read_ppm(&path)
+        .unwrap_or_else(|| panic!("missing or invalid golden image {}", path.display()));
+    assert_eq!((w, h), (WIDTH, HEIGHT), "golden image {name} has the wrong size");
+    assert_eq!(expected.len(), actual.len(), "golden image {name} is truncated");
+
+    let bad = expected.chunks(3)
+        .zip(actual.chunks(3))
+        .filter(|(e, a)| e.iter().zip(a.iter()).any(|(x, y)| x.abs_diff(*y) > CHANNEL_TOLERANCE))
+        .count();
+    let ratio = bad as f32 / (WIDTH * HEIGHT) as f32;
+
+    if ratio > MAX_BAD_PIXELS {
+        let out = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("target").join("golden-failures").join(format!("{name}.ppm"));
+        write_ppm(&out, &actual);
+        panic!(
+            "{name}: {bad} pixels differ from {} ({:.2}%), actual frame written to {}",
+            path.display(), ratio * 100.0, out.display()
+        );
+    }
+}
+
+#[test]
+fn corridor_facing_east() {
+    check_golden("corridor_east", Vector2::new(120.0, 100.0), 0.0, Vec::new(), None);
+}
+
+#[test]
+fn corner_seen_diagonally() {
+    check_golden("corner_diagonal", Vector2::new(120.0, 280.0), -PI / 4.0, Vec::new(), None);
+}
+
+#[test]
+fn facing_supply_and_exit() {
+    check_golden("supply_and_exit", Vector2::new(600.0, 280.0), 0.0, Vec::new(), None);
+}
+
+#[test]
+fn zombie_behind_wall_is_occluded() {
+    let zombies = [
+        Zombie::new(Vector2::new(440.0, 120.0)),
+        Zombie::new(Vector2::new(600.0, 280.0)),
+    ];
+    let sprites = zombies.iter().map(Zombie::sprite).collect();
+    check_golden("zombie_occlusion", Vector2::new(120.0, 120.0), 0.25, sprites, None);
+}
+
+#[test]
+fn floor_texture_per_cell() {
+    let floor_map = load_floor_map(golden_dir().join("floor.txt").to_str().unwrap()).unwrap();
+    check_golden("floor_per_cell", Vector2::new(120.0, 100.0), 0.0, Vec::new(), Some(&floor_map));
+}
+
+#[test]
+fn props_are_depth_sorted_billboards() {
+    let gurney = Sprite {
+        scale: 0.45,
+        ..Sprite::new(Vector2::new(360.0, 110.0), SpriteImage::Texture('b'))
+    };
+    let iv_stand = Sprite {
+        scale: 0.85,
+        ..Sprite::new(Vector2::new(300.0, 80.0), SpriteImage::Texture('i'))
+    };
+    check_golden("props", Vector2::new(120.0, 100.0), 0.0, vec![gurney, iv_stand], None);
+}
@@ -0,0 +1,642 @@
+// input.rs
+// Capa de acciones: el juego pregunta por acciones (avanzar, correr, pausa...)
+// y las teclas, botones y ejes que las disparan salen de controls.txt.
+
+use raylib::prelude::*;
+use raylib::consts::{GamepadAxis, GamepadButton, KeyboardKey, MouseButton};
+use std::collections::HashMap;
+use std::fs;
+
+const PAD: i32 = 0;
+// Un eje cuenta al reasignar solo si se empuja más que esto
+const REBIND_AXIS_THRESHOLD: f32 = 0.6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    MoveForward,
+    MoveBack,
+    StrafeLeft,
+    StrafeRight,
+    TurnLeft,
+    TurnRight,
+    Sprint,
+    Interact,
+    Pause,
+    Controls,
+}
+
+impl Action {
+    pub const ALL: [Action; 10] = [
+        Action::MoveForward,
+        Action::MoveBack,
+        Action::StrafeLeft,
+        Action::StrafeRight,
+        Action::TurnLeft,
+        Action::TurnRight,
+        Action::Sprint,
+        Action::Interact,
+        Action::Pause,
+        Action::Controls,
+    ];
+
+    // Clave en controls.txt
+    pub fn name(self) -> &'static str {
+        match self {
+            Action::MoveForward => "move_forward",
+            Action::MoveBack => "move_back",
+            Action::StrafeLeft => "strafe_left",
+            Action::StrafeRight => "strafe_right",
+            Action::TurnLeft => "turn_left",
+            Action::TurnRight => "turn_right",
+            Action::Sprint => "sprint",
+            Action::Interact => "interact",
+            Action::Pause => "pause",
+            Action::Controls => "controls",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Action::MoveForward => "Move forward",
+            Action::MoveBack => "Move back",
+            Action::StrafeLeft => "Strafe left",
+            Action::StrafeRight => "Strafe right",
+            Action::TurnLeft => "Turn left",
+            Action::TurnRight => "Turn right",
+            Action::Sprint => "Sprint",
+            Action::Interact => "Interact / Start",
+            Action::Pause => "Pause",
+            Action::Controls => "Controls menu",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Action> {
+        Action::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Binding {
+    Key(KeyboardKey),
+    Mouse(MouseButton),
+    PadButton(GamepadButton),
+    // Eje del stick hacia un lado (`true` = valores positivos)
+    PadAxis(GamepadAxis, bool),
+}
+
+const KEY_NAMES: &[(&str, KeyboardKey)] = &[
+    ("APOSTROPHE", KeyboardKey::KEY_APOSTROPHE),
+    ("COMMA", KeyboardKey::KEY_COMMA),
+    ("MINUS", KeyboardKey::KEY_MINUS),
+    ("PERIOD", KeyboardKey::KEY_PERIOD),
+    ("SLASH", KeyboardKey::KEY_SLASH),
+    ("ZERO", KeyboardKey::KEY_ZERO),
+    ("ONE", KeyboardKey::KEY_ONE),
+    ("TWO", KeyboardKey::KEY_TWO),
+    ("THREE", KeyboardKey::KEY_THREE),
+    ("FOUR", KeyboardKey::KEY_FOUR),
+    ("FIVE", KeyboardKey::KEY_FIVE),
+    ("SIX", KeyboardKey::KEY_SIX),
+    ("SEVEN", KeyboardKey::KEY_SEVEN),
+    ("EIGHT", KeyboardKey::KEY_EIGHT),
+    ("NINE", KeyboardKey::KEY_NINE),
+    ("SEMICOLON", KeyboardKey::KEY_SEMICOLON),
+    ("EQUAL", KeyboardKey::KEY_EQUAL),
+    ("A", KeyboardKey::KEY_A),
+    ("B", KeyboardKey::KEY_B),
+    ("C", KeyboardKey::KEY_C),
+    ("D", KeyboardKey::KEY_D),
+    ("E", KeyboardKey::KEY_E),
+    ("F", KeyboardKey::KEY_F),
+    ("G", KeyboardKey::KEY_G),
+    ("H", KeyboardKey::KEY_H),
+    ("I", KeyboardKey::KEY_I),
+    ("J", KeyboardKey::KEY_J),
+    ("K", KeyboardKey::KEY_K),
+    ("L", KeyboardKey::KEY_L),
+    ("M", KeyboardKey::KEY_M),
+    ("N", KeyboardKey::KEY_N),
+    ("O", KeyboardKey::KEY_O),
+    ("P", KeyboardKey::KEY_P),
+    ("Q", KeyboardKey::KEY_Q),
+    ("R", KeyboardKey::KEY_R),
+    ("S", KeyboardKey::KEY_S),
+    ("T", KeyboardKey::KEY_T),
+    ("U", KeyboardKey::KEY_U),
+    ("V", KeyboardKey::KEY_V),
+    ("W", KeyboardKey::KEY_W),
+    ("X", KeyboardKey::KEY_X),
+    ("Y", KeyboardKey::KEY_Y),
+    ("Z", KeyboardKey::KEY_Z),
+    ("LEFT_BRACKET", KeyboardKey::KEY_LEFT_BRACKET),
+    ("BACKSLASH", KeyboardKey::KEY_BACKSLASH),
+    ("RIGHT_BRACKET", KeyboardKey::KEY_RIGHT_BRACKET),
+    ("GRAVE", KeyboardKey::KEY_GRAVE),
+    ("SPACE", KeyboardKey::KEY_SPACE),
+    ("ESCAPE", KeyboardKey::KEY_ESCAPE),
+    ("ENTER", KeyboardKey::KEY_ENTER),
+    ("TAB", KeyboardKey::KEY_TAB),
+    ("BACKSPACE", KeyboardKey::KEY_BACKSPACE),
+    ("INSERT", KeyboardKey::KEY_INSERT),
+    ("DELETE", KeyboardKey::KEY_DELETE),
+    ("RIGHT", KeyboardKey::KEY_RIGHT),
+    ("LEFT", KeyboardKey::KEY_LEFT),
+    ("DOWN", KeyboardKey::KEY_DOWN),
+    ("UP", KeyboardKey::KEY_UP),
+    ("PAGE_UP", KeyboardKey::KEY_PAGE_UP),
+    ("PAGE_DOWN", KeyboardKey::KEY_PAGE_DOWN),
+    ("HOME", KeyboardKey::KEY_HOME),
+    ("END", KeyboardKey::KEY_END),
+    ("CAPS_LOCK", KeyboardKey::KEY_CAPS_LOCK),
+    ("SCROLL_LOCK", KeyboardKey::KEY_SCROLL_LOCK),
+    ("NUM_LOCK", KeyboardKey::KEY_NUM_LOCK),
+    ("PRINT_SCREEN", KeyboardKey::KEY_PRINT_SCREEN),
+    ("PAUSE", KeyboardKey::KEY_PAUSE),
+    ("F1", KeyboardKey::KEY_F1),
+    ("F2", KeyboardKey::KEY_F2),
+    ("F3", KeyboardKey::KEY_F3),
+    ("F4", KeyboardKey::KEY_F4),
+    ("F5", KeyboardKey::KEY_F5),
+    ("F6", KeyboardKey::KEY_F6),
+    ("F7", KeyboardKey::KEY_F7),
+    ("F8", KeyboardKey::KEY_F8),
+    ("F9", KeyboardKey::KEY_F9),
+    ("F10", KeyboardKey::KEY_F10),
+    ("F11", KeyboardKey::KEY_F11),
+    ("F12", KeyboardKey::KEY_F12),
+    ("LEFT_SHIFT", KeyboardKey::KEY_LEFT_SHIFT),
+    ("LEFT_CONTROL", KeyboardKey::KEY_LEFT_CONTROL),
+    ("LEFT_ALT", KeyboardKey::KEY_LEFT_ALT),
+    ("LEFT_SUPER", KeyboardKey::KEY_LEFT_SUPER),
+    ("RIGHT_SHIFT", KeyboardKey::KEY_RIGHT_SHIFT),
+    ("RIGHT_CONTROL", KeyboardKey::KEY_RIGHT_CONTROL),
+    ("RIGHT_ALT", KeyboardKey::KEY_RIGHT_ALT),
+    ("RIGHT_SUPER", KeyboardKey::KEY_RIGHT_SUPER),
+    ("KP_0", KeyboardKey::KEY_KP_0),
+    ("KP_1", KeyboardKey::KEY_KP_1),
+    ("KP_2", KeyboardKey::KEY_KP_2),
+    ("KP_3", KeyboardKey::KEY_KP_3),
+    ("KP_4", KeyboardKey::KEY_KP_4),
+    ("KP_5", KeyboardKey::KEY_KP_5),
+    ("KP_6", KeyboardKey::KEY_KP_6),
+    ("KP_7", KeyboardKey::KEY_KP_7),
+    ("KP_8", KeyboardKey::KEY_KP_8),
+    ("KP_9", KeyboardKey::KEY_KP_9),
+    ("KP_DECIMAL", KeyboardKey::KEY_KP_DECIMAL),
+    ("KP_DIVIDE", KeyboardKey::KEY_KP_DIVIDE),
+    ("KP_MULTIPLY", KeyboardKey::KEY_KP_MULTIPLY),
+    ("KP_SUBTRACT", KeyboardKey::KEY_KP_SUBTRACT),
+    ("KP_ADD", KeyboardKey::KEY_KP_ADD),
+    ("KP_ENTER", KeyboardKey::KEY_KP_ENTER),
+    ("KP_EQUAL", KeyboardKey::KEY_KP_EQUAL),
+];
+
+const MOUSE_NAMES: &[(&str, MouseButton)] = &[
+    ("LEFT", MouseButton::MOUSE_BUTTON_LEFT),
+    ("RIGHT", MouseButton::MOUSE_BUTTON_RIGHT),
+    ("MIDDLE", MouseButton::MOUSE_BUTTON_MIDDLE),
+    ("SIDE", MouseButton::MOUSE_BUTTON_SIDE),
+    ("EXTRA", MouseButton::MOUSE_BUTTON_EXTRA),
+    ("FORWARD", MouseButton::MOUSE_BUTTON_FORWARD),
+    ("BACK", MouseButton::MOUSE_BUTTON_BACK),
+];
+
+const PAD_BUTTON_NAMES: &[(&str, GamepadButton)] = &[
+    ("LEFT_FACE_UP", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_UP),
+    ("LEFT_FACE_RIGHT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_RIGHT),
+    ("LEFT_FACE_DOWN", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_DOWN),
+    ("LEFT_FACE_LEFT", GamepadButton::GAMEPAD_BUTTON_LEFT_FACE_LEFT),
+    ("RIGHT_FACE_UP", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_UP),
+    ("RIGHT_FACE_RIGHT", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_RIGHT),
+    ("RIGHT_FACE_DOWN", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_DOWN),
+    ("RIGHT_FACE_LEFT", GamepadButton::GAMEPAD_BUTTON_RIGHT_FACE_LEFT),
+    ("LEFT_TRIGGER_1", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1),
+    ("LEFT_TRIGGER_2", GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2),
+    ("RIGHT_TRIGGER_1", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1),
+    ("RIGHT_TRIGGER_2", GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2),
+    ("MIDDLE_LEFT", GamepadButton::GAMEPAD_BUTTON_MIDDLE_LEFT),
+    ("MIDDLE", GamepadButton::GAMEPAD_BUTTON_MIDDLE),
+    ("MIDDLE_RIGHT", GamepadButton::GAMEPAD_BUTTON_MIDDLE_RIGHT),
+    ("LEFT_THUMB", GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB),
+    ("RIGHT_THUMB", GamepadButton::GAMEPAD_BUTTON_RIGHT_THUMB),
+];
+
+const PAD_AXIS_NAMES: &[(&str, GamepadAxis)] = &[
+    ("LEFT_X", GamepadAxis::GAMEPAD_AXIS_LEFT_X),
+    ("LEFT_Y", GamepadAxis::GAMEPAD_AXIS_LEFT_Y),
+    ("RIGHT_X", GamepadAxis::GAMEPAD_AXIS_RIGHT_X),
+    ("RIGHT_Y", GamepadAxis::GAMEPAD_AXIS_RIGHT_Y),
+    ("LEFT_TRIGGER", GamepadAxis::GAMEPAD_AXIS_LEFT_TRIGGER),
+    ("RIGHT_TRIGGER", GamepadAxis::GAMEPAD_AXIS_RIGHT_TRIGGER),
+];
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table.iter().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|&(_, v)| v)
+}
+
+fn name_of<T: PartialEq>(table: &[(&'static str, T)], value: T) -> &'static str {
+    table.iter().find(|(_, v)| *v == value).map(|(n, _)| *n).unwrap_or("?")
+}
+
+impl Binding {
+    /// `key:W`, `mouse:LEFT`, `pad:LEFT_THUMB` o `axis:LEFT_Y-`.
+    pub fn parse(text: &str) -> Option<Binding> {
+        let (kind, name) = text.trim().split_once(':')?;
+        let name = name.trim();
+        match kind.trim() {
+            "key" => lookup(KEY_NAMES, name).map(Binding::Key),
+            "mouse" => lookup(MOUSE_NAMES, name).map(Binding::Mouse),
+            "pad" => lookup(PAD_BUTTON_NAMES, name).map(Binding::PadButton),
+            "axis" => {
+                let (axis, positive) = match name.strip_suffix('+') {
+                    Some(axis) => (axis, true),
+                    None => (name.strip_suffix('-')?, false),
+                };
+                lookup(PAD_AXIS_NAMES, axis).map(|a| Binding::PadAxis(a, positive))
+            }
+            _ => None,
+        }
+    }
+
+    pub fn is_gamepad(self) -> bool {
+        matches!(self, Binding::PadButton(_) | Binding::PadAxis(..))
+    }
+}
+
+impl std::fmt::Display for Binding {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Binding::Key(k) => write!(f, "key:{}", name_of(KEY_NAMES, k)),
+            Binding::Mouse(b) => write!(f, "mouse:{}", name_of(MOUSE_NAMES, b)),
+            Binding::PadButton(b) => write!(f, "pad:{}", name_of(PAD_BUTTON_NAMES, b)),
+            Binding::PadAxis(a, positive) => {
+                write!(f, "axis:{}{}", name_of(PAD_AXIS_NAMES, a), if positive { '+' } else { '-' })
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InputSettings {
+    // Radianes por pixel de mouse
+    pub mouse_sensitivity: f32,
+    // Radianes por segundo con el stick a fondo
+    pub stick_sensitivity: f32,
+    pub deadzone: f32,
+}
+
+impl Default for InputSettings {
+    fn default() -> Self {
+        InputSettings {
+            mouse_sensitivity: 0.0030,
+            stick_sensitivity: 2.9,
+            deadzone: 0.15,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bindings {
+    map: HashMap<Action, Vec<Binding>>,
+    pub settings: InputSettings,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Bindings::parse(DEFAULT_CONTROLS).expect("default controls are valid")
+    }
+}
+
+// Los mismos controles de siempre; controls.txt los sobrescribe
+const DEFAULT_CONTROLS: &str = "\
+move_forward = key:W, key:UP, axis:LEFT_Y-
+move_back = key:S, key:DOWN, axis:LEFT_Y+
+strafe_left = key:A, key:Q, axis:LEFT_X-, pad:LEFT_TRIGGER_1, pad:LEFT_TRIGGER_2
+strafe_right = key:D, key:E, axis:LEFT_X+, pad:RIGHT_TRIGGER_1, pad:RIGHT_TRIGGER_2
+turn_left = key:LEFT, axis:RIGHT_X-
+turn_right = key:RIGHT, axis:RIGHT_X+
+sprint = key:LEFT_SHIFT, pad:LEFT_THUMB
+interact = key:ENTER, key:SPACE, pad:RIGHT_FACE_DOWN
+pause = key:P, pad:MIDDLE_RIGHT
+controls = key:C, pad:MIDDLE_LEFT
+";
+
+impl Bindings {
+    /// Lee líneas `accion = entrada, entrada` y `ajuste = valor`. Las acciones
+    /// que no aparecen quedan sin entradas.
+    pub fn parse(text: &str) -> Result<Bindings, String> {
+        let mut bindings = Bindings { map: HashMap::new(), settings: InputSettings::default() };
+
+        for (n, raw) in text.lines().enumerate() {
+            let line_no = n + 1;
+            let line = raw.split('#').next().unwrap_or("").trim();
+            if line.is_empty() { continue; }
+
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(format!("line {line_no}: expected 'name = value', got '{line}'"));
+            };
+            let (key, value) = (key.trim(), value.trim());
+            let number = || value.parse::<f32>()
+                .ok()
+                .filter(|v| v.is_finite() && *v >= 0.0)
+                .ok_or_else(|| format!("line {line_no}: '{key}' needs a non-negative number, got '{value}'"));
+
+            match key {
+                "mouse_sensitivity" => bindings.settings.mouse_sensitivity = number()?,
+                "stick_sensitivity" => bindings.settings.stick_sensitivity = number()?,
+                "deadzone" => bindings.settings.deadzone = number()?.min(0.95),
+                // Ajuste viejo sin efecto real; se acepta para no rechazar
+                // archivos guardados antes. Para invertir el stick se
+                // intercambian axis:LEFT_Y- y axis:LEFT_Y+
+                "invert_y" => {}
+                _ => {
+                    let action = Action::from_name(key)
+                        .ok_or_else(|| format!("line {line_no}: unknown action '{key}'"))?;
+                    let list = value.split(',')
+                        .map(str::trim)
+                        .filter(|b| !b.is_empty())
+                        .map(|b| Binding::parse(b).ok_or_else(|| format!("line {line_no}: unknown input '{b}'")))
+                        .collect::<Result<Vec<_>, _>>()?;
+                    bindings.map.insert(action, list);
+                }
+            }
+        }
+        Ok(bindings)
+    }
+
+    pub fn load(filename: &str) -> Bindings {
+        let text = match fs::read_to_string(filename) {
+            Ok(text) => text,
+            Err(_) => return Bindings::default(),
+        };
+        match Bindings::parse(&text) {
+            Ok(bindings) => {
+                println!("Loaded controls from '{}'", filename);
+                bindings
+            }
+            Err(e) => {
+                eprintln!("ERROR in '{}': {}", filename, e);
+                eprintln!("Using default controls");
+                Bindings::default()
+            }
+        }
+    }
+
+    pub fn save(&self, filename: &str) {
+        match fs::write(filename, self.to_config()) {
+            Ok(()) => println!("Controls saved to '{}'", filename),
+            Err(e) => eprintln!("WARNING: could not write '{}': {}", filename, e),
+        }
+    }
+
+    pub fn to_config(&self) -> String {
+        let mut out = String::from(
+            "# Controles. Cada acción lleva una lista de entradas separadas por comas:\n\
+             #   key:W  mouse:LEFT  pad:LEFT_THUMB  axis:LEFT_Y- (stick hacia el lado negativo)\n",
+        );
+        for action in Action::ALL {
+            let list: Vec<String> = self.get(action).iter().map(Binding::to_string).collect();
+            out += &format!("{} = {}\n", action.name(), list.join(", "));
+        }
+        let s = &self.settings;
+        out += &format!(
+            "\nmouse_sensitivity = {}\nstick_sensitivity = {}\ndeadzone = {}\n",
+            s.mouse_sensitivity, s.stick_sensitivity, s.deadzone
+        );
+        out
+    }
+
+    pub fn get(&self, action: Action) -> &[Binding] {
+        self.map.get(&action).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Nombre de la primera entrada de la acción (mejor de teclado o mouse),
+    /// para los textos de ayuda.
+    pub fn hint(&self, action: Action) -> String {
+        let list = self.get(action);
+        match list.iter().find(|b| !b.is_gamepad()).or(list.first()) {
+            Some(b) => {
+                let text = b.to_string();
+                text.split_once(':').map_or(text.clone(), |(_, name)| name.to_string())
+            }
+            None => "-".to_string(),
+        }
+    }
+
+    /// Asigna `binding` a `action`: reemplaza las entradas del mismo tipo de
+    /// dispositivo (teclado/mouse o mando) y se la quita a otras acciones.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        for list in self.map.values_mut() {
+            list.retain(|&b| b != binding);
+        }
+        let list = self.map.entry(action).or_default();
+        list.retain(|b| b.is_gamepad() != binding.is_gamepad());
+        list.push(binding);
+    }
+
+    pub fn clear(&mut self, action: Action) {
+        self.map.remove(&action);
+    }
+
+    fn axis_value(&self, rl: &RaylibHandle, axis: GamepadAxis, positive: bool) -> f32 {
+        let v = rl.get_gamepad_axis_movement(PAD, axis);
+        if v.abs() < self.settings.deadzone { return 0.0; }
+        if positive { v.max(0.0) } else { (-v).max(0.0) }
+    }
+
+    /// Alguna tecla o botón (no ejes) de la acción está apretado.
+    pub fn digital(&self, rl: &RaylibHandle, action: Action) -> bool {
+        let pad = rl.is_gamepad_available(PAD);
+        self.get(action).iter().any(|&b| match b {
+            Binding::Key(k) => rl.is_key_down(k),
+            Binding::Mouse(m) => rl.is_mouse_button_down(m),
+            Binding::PadButton(p) => pad && rl.is_gamepad_button_down(PAD, p),
+            Binding::PadAxis(..) => false,
+        })
+    }
+
+    /// Cuánto se empujan los ejes de la acción, 0..1 tras la zona muerta.
+    pub fn analog(&self, rl: &RaylibHandle, action: Action) -> f32 {
+        if !rl.is_gamepad_available(PAD) { return 0.0; }
+        self.get(action).iter()
+            .filter_map(|&b| match b {
+                Binding::PadAxis(a, positive) => Some(self.axis_value(rl, a, positive)),
+                _ => None,
+            })
+            .fold(0.0, f32::max)
+    }
+
+    pub fn value(&self, rl: &RaylibHandle, action: Action) -> f32 {
+        if self.digital(rl, action) { 1.0 } else { self.analog(rl, action) }
+    }
+
+    pub fn is_down(&self, rl: &RaylibHandle, action: Action) -> bool {
+        self.value(rl, action) > 0.0
+    }
+
+    pub fn is_pressed(&self, rl: &RaylibHandle, action: Action) -> bool {
+        let pad = rl.is_gamepad_available(PAD);
+        self.get(action).iter().any(|&b| match b {
+            Binding::Key(k) => rl.is_key_pressed(k),
+            Binding::Mouse(m) => rl.is_mouse_button_pressed(m),
+            Binding::PadButton(p) => pad && rl.is_gamepad_button_pressed(PAD, p),
+            Binding::PadAxis(..) => false,
+        })
+    }
+}
+
+// Filas del menú de controles después de las acciones
+pub const SETTING_ROWS: [&str; 3] = ["Mouse sensitivity", "Stick sensitivity", "Stick deadzone"];
+
+/// Pantalla para reasignar controles. Flechas eligen fila, Enter espera la
+/// nueva entrada, Supr borra, Izq/Der cambian ajustes, Retroceso vuelve.
+pub struct RebindMenu {
+    pub selected: usize,
+    pub listening: bool,
+}
+
+impl RebindMenu {
+    pub fn new() -> Self {
+        RebindMenu { selected: 0, listening: false }
+    }
+
+    pub fn rows() -> usize {
+        Action::ALL.len() + SETTING_ROWS.len()
+    }
+
+    // Primera entrada nueva de este frame, de cualquier dispositivo
+    fn captured_binding(rl: &mut RaylibHandle) -> Option<Binding> {
+        if let Some(key) = rl.get_key_pressed() {
+            return Some(Binding::Key(key));
+        }
+        if let Some(&(_, m)) = MOUSE_NAMES.iter().find(|(_, m)| rl.is_mouse_button_pressed(*m)) {
+            return Some(Binding::Mouse(m));
+        }
+        if !rl.is_gamepad_available(PAD) { return None; }
+        if let Some(p) = rl.get_gamepad_button_pressed() {
+            return Some(Binding::PadButton(p));
+        }
+        PAD_AXIS_NAMES.iter().find_map(|&(_, a)| {
+            let v = rl.get_gamepad_axis_movement(PAD, a);
+            (v.abs() > REBIND_AXIS_THRESHOLD).then_some(Binding::PadAxis(a, v > 0.0))
+        })
+    }
+
+    /// Procesa la entrada de un frame. Devuelve `true` al salir del menú.
+    pub fn update(&mut self, rl: &mut RaylibHandle, bindings: &mut Bindings) -> bool {
+        if self.listening {
+            if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+                self.listening = false;
+            } else if let Some(binding) = Self::captured_binding(rl) {
+                bindings.rebind(Action::ALL[self.selected], binding);
+                self.listening = false;
+            }
+            return false;
+        }
+
+        if rl.is_key_pressed(KeyboardKey::KEY_BACKSPACE) {
+            return true;
+        }
+        let rows = Self::rows();
+        if rl.is_key_pressed(KeyboardKey::KEY_DOWN) { self.selected = (self.selected + 1) % rows; }
+        if rl.is_key_pressed(KeyboardKey::KEY_UP) { self.selected = (self.selected + rows - 1) % rows; }
+
+        let mut step = 0.0;
+        if rl.is_key_pressed(KeyboardKey::KEY_RIGHT) { step += 1.0; }
+        if rl.is_key_pressed(KeyboardKey::KEY_LEFT) { step -= 1.0; }
+        let enter = rl.is_key_pressed(KeyboardKey::KEY_ENTER);
+
+        if let Some(&action) = Action::ALL.get(self.selected) {
+            if enter {
+                self.listening = true;
+                // El Enter que abrió la escucha no cuenta como la nueva tecla
+                while rl.get_key_pressed().is_some() {}
+            } else if rl.is_key_pressed(KeyboardKey::KEY_DELETE) {
+                bindings.clear(action);
+            }
+        } else {
+            let s = &mut bindings.settings;
+            match self.selected - Action::ALL.len() {
+                0 => s.mouse_sensitivity = (s.mouse_sensitivity + step * 0.0005).clamp(0.0005, 0.02),
+                1 => s.stick_sensitivity = (s.stick_sensitivity + step * 0.1).clamp(0.5, 8.0),
+                _ => s.deadzone = (s.deadzone + step * 0.05).clamp(0.0, 0.9),
+            }
+        }
+        false
+    }
+
+    // Texto de cada fila: (nombre, valor)
+    pub fn row_text(&self, bindings: &Bindings, row: usize) -> (String, String) {
+        if let Some(&action) = Action::ALL.get(row) {
+            let value = if self.listening && row == self.selected {
+                "press a key or button... (Backspace cancels)".to_string()
+            } else {
+                let list: Vec<String> = bindings.get(action).iter().map(Binding::to_string).collect();
+                if list.is_empty() { "-".to_string() } else { list.join("  ") }
+            };
+            return (action.label().to_string(), value);
+        }
+        let s = &bindings.settings;
+        let idx = row - Action::ALL.len();
+        let value = match idx {
+            0 => format!("{:.4}", s.mouse_sensitivity),
+            1 => format!("{:.1}", s.stick_sensitivity),
+            _ => format!("{:.2}", s.deadzone),
+        };
+        (SETTING_ROWS[idx].to_string(), value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_round_trips() {
+        let mut bindings = Bindings::default();
+        bindings.settings.stick_sensitivity = 3.5;
+        bindings.settings.deadzone = 0.25;
+        let again = Bindings::parse(&bindings.to_config()).unwrap();
+        assert_eq!(again, bindings);
+        // El controls.txt del repo son los valores por defecto
+        assert_eq!(Bindings::parse(include_str!("../controls.txt")).unwrap(), Bindings::default());
+        assert_eq!(
+            again.get(Action::MoveForward),
+            &[Binding::Key(KeyboardKey::KEY_W), Binding::Key(KeyboardKey::KEY_UP),
+              Binding::PadAxis(GamepadAxis::GAMEPAD_AXIS_LEFT_Y, false)]
+        );
+    }
+
+    #[test]
+    fn parse_errors_report_the_line() {
+        assert_eq!(Bindings::parse("jump = key:SPACE").unwrap_err(), "line 1: unknown action 'jump'");
+        assert_eq!(Bindings::parse("\nsprint = key:NOPE").unwrap_err(), "line 2: unknown input 'key:NOPE'");
+        assert!(Bindings::parse("deadzone = -1").is_err());
+        // Un controls.txt viejo con invert_y se sigue leyendo
+        assert_eq!(Bindings::parse("invert_y = true\ncontrols = key:TAB").unwrap().hint(Action::Controls), "TAB");
+        assert_eq!(Binding::parse("axis:RIGHT_X+"), Some(Binding::PadAxis(GamepadAxis::GAMEPAD_AXIS_RIGHT_X, true)));
+    }
+
+    #[test]
+    fn rebinding_moves_the_input_and_keeps_the_other_device() {
+        let mut bindings = Bindings::default();
+        // W pasa de avanzar a correr; correr conserva L3 pero pierde Shift
+        bindings.rebind(Action::Sprint, Binding::Key(KeyboardKey::KEY_W));
+        assert_eq!(
+            bindings.get(Action::Sprint),
+            &[Binding::PadButton(GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB), Binding::Key(KeyboardKey::KEY_W)]
+        );
+        assert!(!bindings.get(Action::MoveForward).contains(&Binding::Key(KeyboardKey::KEY_W)));
+        assert!(bindings.get(Action::MoveForward).contains(&Binding::Key(KeyboardKey::KEY_UP)));
+    }
+
+    #[test]
+    fn controls_menu_is_a_rebindable_action() {
+        let mut bindings = Bindings::default();
+        assert_eq!(bindings.hint(Action::Controls), "C");
+        bindings.rebind(Action::Controls, Binding::Key(KeyboardKey::KEY_F1));
+        assert_eq!(bindings.hint(Action::Controls), "F1");
+        bindings.clear(Action::Controls);
+        assert_eq!(bindings.hint(Action::Controls), "-");
+    }
+}
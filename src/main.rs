@@ -13,35 +13,48 @@ mod sprites;
 mod zombie;
 mod texture;
 mod audio;
+mod renderer;
+mod nav;
+mod perception;
+mod archetypes;
+mod tiles;
+mod input;
+mod analysis;
+mod mapgen;
+
+#[cfg(test)]
+mod golden_tests;
 
 use line::line;
-use maze::{Maze, load_maze};
+use maze::{Maze, Level, default_level, load_level, load_floor_map, validate_maze};
 use caster::{cast_ray, Intersect};
 use framebuffer::Framebuffer;
 use player::{Player, process_events};
 use game_state::{GameState, GameData};
 use ui_renderer::*;
-use zombie::Zombie;
+use zombie::{propagate_alerts, resolve_overlaps, Zombie};
+use archetypes::{load_archetypes, pick_archetype, take_placements, Archetype};
+use tiles::{tile, is_zombie_walkable, Pickup};
+use input::{Action, Bindings, RebindMenu};
+use analysis::analyze;
+use mapgen::{generate_with, Algorithm, MapGenOptions};
+use nav::{cell_at, Cell, NavField};
+use sprites::maze_sprites;
 use texture::TextureManager;
 use audio::AudioManager;
+use renderer::{Scene, render_world};
 use raylib::prelude::*;
 use std::thread;
 use std::time::{Duration, Instant};
 use std::f32::consts::PI;
 use crate::ui_renderer::{render_main_menu_into, render_defeat_screen, draw_timer_into};
 use rand::Rng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::SeedableRng;
 
 fn cell_to_color(cell: char) -> Color {
-  match cell {
-    '+' => Color::new(120, 120, 180, 255),
-    '-' => Color::new(140, 100, 100, 255),
-    '|' => Color::new(100, 140, 100, 255),
-    '#' => Color::new(200, 60, 60, 255),
-    'X' => Color::new(60, 180, 60, 255),
-    'g' => Color::new(0, 255, 0, 255),
-    'M' => Color::new(0, 150, 255, 255),
-    _ => Color::new(100, 100, 100, 255),
-  }
+  tile(cell).map_color
 }
 
 fn draw_cell(
@@ -84,16 +97,17 @@ pub fn render_maze(
   for i in 0..num_rays {
     let current_ray = i as f32 / num_rays as f32;
     let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-    cast_ray(framebuffer, &maze, player, a, block_size, true);
+    cast_ray(framebuffer, maze, player, a, block_size, true);
   }
 }
 
-fn random_free_spawn(maze: &Vec<Vec<char>>, block_size: usize, rng: &mut impl Rng) -> Option<(f32,f32)> {
+fn random_free_spawn(maze: &[Vec<char>], block_size: usize, rng: &mut impl Rng) -> Option<(f32,f32)> {
     for _ in 0..500 {
         let j = rng.gen_range(0..maze.len());
-        let i = rng.gen_range(0..maze[0].len());
+        if maze[j].is_empty() { continue; }
+        let i = rng.gen_range(0..maze[j].len());
         let c = maze[j][i];
-        if c == ' ' || c == 'M' || c == 'g' {
+        if is_zombie_walkable(c) {
             let x = i as f32 * block_size as f32 + (block_size as f32 * 0.5);
             let y = j as f32 * block_size as f32 + (block_size as f32 * 0.5);
             return Some((x, y));
@@ -102,246 +116,79 @@ fn random_free_spawn(maze: &Vec<Vec<char>>, block_size: usize, rng: &mut impl Rn
     None
 }
 
-fn spawn_zombies(maze: &Vec<Vec<char>>, block_size: usize, count: usize, avoid: Vector2) -> Vec<Zombie> {
-    let mut rng = rand::thread_rng();
-    let mut zs = Vec::with_capacity(count);
-    while zs.len() < count {
-        if let Some((x, y)) = random_free_spawn(maze, block_size, &mut rng) {
-            let d2 = (x - avoid.x)*(x - avoid.x) + (y - avoid.y)*(y - avoid.y);
-            if d2 > (block_size as f32 * 4.0).powi(2) { // no spawnear pegado al jugador
-                zs.push(Zombie::new(Vector2::new(x, y)));
-            }
-        } else { break; }
+// Ni los puntos de spawn ni los zombies al azar se usan a menos de esta
+// cantidad de bloques del jugador
+const SPAWN_MIN_BLOCKS: f32 = 4.0;
+
+// Si el mapa coloca zombies se usan esos; si no, los que pida el nivel, en sus
+// puntos de spawn o al azar lejos del jugador, con el arquetipo según su peso
+fn spawn_zombies(
+    level: &Level,
+    block_size: usize,
+    archetypes: &[Archetype],
+    placements: &[(Cell, usize)],
+    avoid: Vector2,
+    rng: &mut impl Rng,
+) -> Vec<Zombie> {
+    let bs = block_size as f32;
+    if !placements.is_empty() {
+        return placements.iter()
+            .map(|&((i, j), idx)| {
+                let pos = Vector2::new((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs);
+                Zombie::from_archetype(pos, &archetypes[idx])
+            })
+            .collect();
     }
-    zs
-}
-
-fn render_world_optimized_into(
-  d: &mut RaylibDrawHandle,
-  screen_width: f32,
-  screen_height: f32,
-  maze: &Maze,
-  block_size: usize,
-  player: &Player,
-  zombies: &[Zombie],
-  texture_manager: &TextureManager,
-  time_s: f32,
-) {
-  let num_rays = screen_width as usize;
-  let hh = screen_height / 2.0;
-
-  fn draw_band_repeat(
-      d: &mut RaylibDrawHandle,
-      tex: &Texture2D,
-      dest: Rectangle,
-      scale: f32,
-      scroll_x: f32,
-      tint: Color,
-  ) {
-      let tile_w = tex.width() as f32 * scale;
-      if tile_w <= 0.5 { return; }
-      let src = Rectangle::new(0.0, 0.0, tex.width() as f32, tex.height() as f32);
-
-      let mut start_x = -((scroll_x % tile_w + tile_w) % tile_w);
-      while start_x < dest.width {
-          let dx = dest.x + start_x;
-          d.draw_texture_pro(
-              tex,
-              src,
-              Rectangle::new(dx, dest.y, tile_w, dest.height),
-              Vector2::new(0.0, 0.0),
-              0.0,
-              tint,
-          );
-          start_x += tile_w;
-      }
-  }
-
-  d.clear_background(Color::new(30, 30, 40, 255));
-
-  let ceiling_tex = texture_manager.get_ceiling_texture().expect("ceiling tex");
-  let floor_tex   = texture_manager.get_floor_texture().expect("floor tex");
-
-  let bands: i32 = 60;
-  let screen_w   = screen_width;
-  let screen_h   = screen_height;
-
-  let pos_factor  = 0.04;
-  let rot_amp     = 2.5;
-  let rot_speed   = 0.35;
-  let uv_scroll_x = -(player.pos.x * pos_factor + player.a.cos() * (time_s * rot_speed) * rot_amp);
-
-  // Techo
-  for b in 0..bands {
-      let b = b as f32;
-      let y0 = (b / bands as f32) * hh;
-      let y1 = ((b + 1.0) / bands as f32) * hh;
-      let band_h = (y1 - y0).max(1.0);
-      let dest = Rectangle::new(0.0, y0, screen_w, band_h);
-
-      let t = (b + 0.5) / bands as f32;
-      let scale = 0.10 + t * 0.90;
-      let shade = (0.60 + 0.40 * t).min(1.0);
-      let tint = Color::new((255.0*shade) as u8, (255.0*shade) as u8, (255.0*shade) as u8, 255);
-
-      draw_band_repeat(d, ceiling_tex, dest, scale, uv_scroll_x, tint);
-  }
-
-  // Suelo
-  for b in 0..bands {
-      let b = b as f32;
-      let y0 = hh + (b / bands as f32) * hh;
-      let y1 = hh + ((b + 1.0) / bands as f32) * hh;
-      let band_h = (y1 - y0).max(1.0);
-      let dest = Rectangle::new(0.0, y0, screen_w, band_h);
-
-      let t = (b + 0.5) / bands as f32;
-      let scale = 0.10 + t * 1.10;
-      let shade = (0.50 + 0.50 * t).min(1.0);
-      let tint = Color::new((240.0*shade) as u8, (240.0*shade) as u8, (240.0*shade) as u8, 255);
-
-      draw_band_repeat(d, floor_tex, dest, scale, uv_scroll_x * 1.2, tint);
-  }
-
-  // Muros
-  let mut zbuffer = vec![f32::INFINITY; screen_width as usize];
-
-  let distance_to_projection_plane = 100.0;
-
-  for i in (0..num_rays).step_by(2) {
-    let current_ray = i as f32 / num_rays as f32;
-    let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
-
-    let mut dummy_fb = Framebuffer::new(1, 1);
-    let intersect = cast_ray(&mut dummy_fb, maze, player, a, block_size, false);
 
-    let distance_to_wall = (intersect.distance * (a - player.a).cos()).max(0.0001);
-    let mut stake_height = (hh / distance_to_wall) * distance_to_projection_plane;
-    if !stake_height.is_finite() { stake_height = screen_h; }
-    let stake_height = stake_height.min(screen_h);
-
-    let stake_top = ((hh - (stake_height / 2.0)) as i32).max(0);
-    let stake_bottom = ((hh + (stake_height / 2.0)) as i32).min(screen_h as i32);
-
-    let mut wall_color = match intersect.impact {
-      '+' | '-' | '|' | '#' => Color::LIGHTGRAY,
-      'g' => Color::GREEN,
-      'M' => Color::BLUE,
-      _   => Color::GRAY,
+    let far_from_player = |x: f32, y: f32| {
+        (x - avoid.x)*(x - avoid.x) + (y - avoid.y)*(y - avoid.y) > (bs * SPAWN_MIN_BLOCKS).powi(2)
     };
-    let fog_factor = (200.0 / distance_to_wall).clamp(0.3, 1.0);
-    wall_color.r = (wall_color.r as f32 * fog_factor) as u8;
-    wall_color.g = (wall_color.g as f32 * fog_factor) as u8;
-    wall_color.b = (wall_color.b as f32 * fog_factor) as u8;
-
-    if let Some(wtex) = texture_manager.get_wall_texture(intersect.impact) {
-        let bs = block_size as f32;
-        let fx = (intersect.hit_x.rem_euclid(bs)) / bs;
-        let fy = (intersect.hit_y.rem_euclid(bs)) / bs;
-        let edge_x = fx.min(1.0 - fx);
-        let edge_y = fy.min(1.0 - fy);
-        let tex_u = if edge_x < edge_y { fy } else { fx };
-
-        let src_x = (tex_u * wtex.width() as f32).floor().clamp(0.0, (wtex.width() - 1) as f32);
-        let src = Rectangle::new(src_x, 0.0, 1.0, wtex.height() as f32);
-
-        let dest = Rectangle::new(i as f32, stake_top as f32, 2.0, stake_height);
-        let tint = Color::new(
-            (255.0 * fog_factor) as u8,
-            (255.0 * fog_factor) as u8,
-            (255.0 * fog_factor) as u8,
-            255,
-        );
 
-        d.draw_texture_pro(wtex, src, dest, Vector2::new(0.0, 0.0), 0.0, tint);
-
-        zbuffer[i] = distance_to_wall;
-        if i + 1 < zbuffer.len() {
-            zbuffer[i + 1] = distance_to_wall;
-        }
-    } else {
-        d.draw_line(i as i32, stake_top, i as i32, stake_bottom, wall_color);
-        zbuffer[i] = distance_to_wall;
-        if i + 1 < zbuffer.len() {
-            zbuffer[i + 1] = distance_to_wall;
+    let count = level.zombie_count;
+    let mut zs = Vec::with_capacity(count);
+    // Puntos de spawn pegados al jugador no cuentan; si no queda ninguno, al azar
+    let spawn_points: Vec<Vector2> = level.spawn_points.iter()
+        .map(|&(i, j)| Vector2::new((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs))
+        .filter(|p| far_from_player(p.x, p.y))
+        .collect();
+    if !spawn_points.is_empty() {
+        for _ in 0..count {
+            let &pos = spawn_points.choose(rng).unwrap();
+            if let Some(arch) = pick_archetype(archetypes, rng) {
+                zs.push(Zombie::from_archetype(pos, arch));
+            }
         }
+        return zs;
     }
-  }
-
-  if let Some(ztex) = texture_manager.get_zombie_texture() {
-      let mut order: Vec<(usize, f32)> = zombies.iter()
-          .enumerate()
-          .map(|(idx, z)| {
-              let dx = z.pos.x - player.pos.x;
-              let dy = z.pos.y - player.pos.y;
-              (idx, (dx*dx + dy*dy).sqrt())
-          })
-          .collect();
-      order.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
-
-      let tex_w = ztex.width() as f32;
-      let tex_h = ztex.height() as f32;
-      let aspect = tex_w / tex_h;
 
-      for (idx, _dist) in order {
-          let z = &zombies[idx];
-          let dx = z.pos.x - player.pos.x;
-          let dy = z.pos.y - player.pos.y;
-
-          let mut ang = dy.atan2(dx) - player.a;
-          while ang >  std::f32::consts::PI { ang -= 2.0*std::f32::consts::PI; }
-          while ang < -std::f32::consts::PI { ang += 2.0*std::f32::consts::PI; }
-
-          if ang.abs() > player.fov * 0.75 { continue; }
-
-          let perp = (dx*dx + dy*dy).sqrt() * ang.cos();
-          if perp <= 0.01 { continue; }
-
-          let sprite_h = (hh / perp) * distance_to_projection_plane;
-          if !sprite_h.is_finite() || sprite_h <= 1.0 { continue; }
-          let sprite_w = sprite_h * aspect;
-
-          let center_x = ((ang / player.fov) + 0.5) * screen_w;
-          let top = (hh - sprite_h/2.0).max(0.0);
-          let left = (center_x - sprite_w/2.0).floor() as i32;
-          let right = (center_x + sprite_w/2.0).ceil() as i32;
-
-          let sx0 = left.max(0);
-          let sx1 = right.min((screen_w as i32) - 1);
-          if sx0 >= sx1 { continue; }
-
-          let fog = (220.0 / perp).clamp(0.35, 1.0);
-          let tint = Color::new((255.0*fog) as u8, (255.0*fog) as u8, (255.0*fog) as u8, 255);
-
-          for sx in sx0..=sx1 {
-              let col = sx as usize;
-              if col >= zbuffer.len() { break; }
-              if perp >= zbuffer[col] { continue; } 
-
-              let u = ((sx as f32 - (center_x - sprite_w/2.0)) / sprite_w)
-                      .clamp(0.0, 1.0);
-              let src_x = (u * tex_w).floor().clamp(0.0, tex_w - 1.0);
-
-              let src = Rectangle::new(src_x, 0.0, 1.0, tex_h);
-              let dest = Rectangle::new(sx as f32, top, 1.0, sprite_h);
-
-              d.draw_texture_pro(ztex, src, dest, Vector2::new(0.0, 0.0), 0.0, tint);
-          }
-      }
-  }
-
-  render_minimap_direct(d, maze, player, zombies, block_size, screen_width as i32, screen_height as i32);
+    let mut tries = 0;
+    while zs.len() < count && tries < 2000 {
+        tries += 1;
+        let Some((x, y)) = random_free_spawn(&level.maze, block_size, rng) else { break; };
+        if far_from_player(x, y) {
+            if let Some(arch) = pick_archetype(archetypes, rng) {
+                zs.push(Zombie::from_archetype(Vector2::new(x, y), arch));
+            }
+        }
+    }
+    if zs.is_empty() && count > 0 {
+        zs = vec![
+            Zombie::new(Vector2::new(200.0, 200.0)),
+            Zombie::new(Vector2::new(400.0, 300.0)),
+            Zombie::new(Vector2::new(600.0, 400.0)),
+        ];
+    }
+    zs
 }
 
-
 fn render_minimap_direct(
   d: &mut RaylibDrawHandle,
   maze: &Maze,
   player: &Player,
   zombies: &[Zombie],
+  nav_debug: Option<&NavField>,
   block_size: usize,
   screen_width: i32,
-  _screen_height: i32,
 ) {
   let diameter = 140;          
   let radius = diameter / 2;
@@ -352,7 +199,7 @@ fn render_minimap_direct(
   d.draw_circle_lines(cx, cy, radius as f32, Color::new(220, 220, 220, 200));
 
   let rows = maze.len() as f32;
-  let cols = maze[0].len() as f32;
+  let cols = maze.iter().map(|row| row.len()).max().unwrap_or(0) as f32;
 
   let fill = 0.92;
   let usable = diameter as f32 * fill;
@@ -375,14 +222,26 @@ fn render_minimap_direct(
     }
   };
 
+  // Debug (F3): distancia del campo de navegación (claro = cerca del jugador)
+  if let Some(field) = nav_debug {
+    let max_d = field.max_distance().max(1) as f32;
+    for (r, row) in maze.iter().enumerate() {
+      for c in 0..row.len() {
+        if let Some(dist) = field.distance((c, r)) {
+          let t = 1.0 - dist as f32 / max_d;
+          let x = origin_x + (c as f32 * scale) as i32;
+          let y = origin_y + (r as f32 * scale) as i32;
+          let sz = scale.max(2.0) as i32;
+          draw_rect_in_circle(x, y, sz, sz, Color::new((255.0 * t) as u8, (120.0 * t) as u8, 40, 160));
+        }
+      }
+    }
+  }
+
   for (r, row) in maze.iter().enumerate() {
     for (c, &cell) in row.iter().enumerate() {
       if cell != ' ' {
-        let color = match cell {
-          'g' => Color::GREEN,
-          'M' => Color::CYAN,
-          _   => Color::WHITE,
-        };
+        let color = tile(cell).minimap_color;
         let x = origin_x + (c as f32 * scale) as i32;
         let y = origin_y + (r as f32 * scale) as i32;
         let sz = scale.max(2.0) as i32; 
@@ -410,7 +269,13 @@ fn render_minimap_direct(
     let dxz = zx - cx;
     let dyz = zy - cy;
     if (dxz * dxz + dyz * dyz) <= (radius * radius) {
-      d.draw_circle(zx, zy, 2.0, Color::RED);
+      if nav_debug.is_some() {
+        // Debug: color y nombre del estado de cada zombie
+        d.draw_circle(zx, zy, 3.0, z.state.debug_color());
+        d.draw_text(z.state.name(), zx + 4, zy - 4, 8, z.state.debug_color());
+      } else {
+        d.draw_circle(zx, zy, 2.0, Color::RED);
+      }
     }
   }
 }
@@ -426,14 +291,12 @@ fn check_collisions_and_pickups(
   let player_i = (player.pos.x / block_size as f32) as usize;
   let player_j = (player.pos.y / block_size as f32) as usize;
   
-  if player_j >= maze.len() || player_i >= maze[0].len() {
+  let Some(&cell) = maze.get(player_j).and_then(|row| row.get(player_i)) else {
     return false;
-  }
-  
-  let cell = maze[player_j][player_i];
+  };
   
-  match cell {
-    'M' => {
+  match tile(cell).pickup {
+    Some(Pickup::MedicalSupply) => {
       maze[player_j][player_i] = ' ';
       game_data.collect_medical_supply();
       if let Some(ref mut audio) = audio_manager {
@@ -443,7 +306,7 @@ fn check_collisions_and_pickups(
         game_data.medical_supplies_collected, game_data.total_medical_supplies);
       false
     },
-    'g' => {
+    Some(Pickup::Exit) => {
       if game_data.can_escape() {
         game_data.game_state = GameState::Victory;
         if let Some(ref mut audio) = audio_manager {
@@ -456,7 +319,7 @@ fn check_collisions_and_pickups(
         false
       }
     },
-    _ => false
+    None => false
   }
 }
 
@@ -464,7 +327,7 @@ fn count_medical_supplies(maze: &Maze) -> u32 {
   let mut count = 0;
   for row in maze {
     for &cell in row {
-      if cell == 'M' {
+      if tile(cell).pickup == Some(Pickup::MedicalSupply) {
         count += 1;
       }
     }
@@ -472,11 +335,169 @@ fn count_medical_supplies(maze: &Maze) -> u32 {
   count
 }
 
+// Carga un nivel y saca los zombies que coloca el mapa
+fn load_level_with_placements(filename: &str, archetypes: &[Archetype]) -> (Level, Vec<(Cell, usize)>) {
+  let markers: Vec<char> = archetypes.iter().filter_map(|a| a.marker).collect();
+  let mut level = match load_level(filename, &markers) {
+    Ok(level) => level,
+    Err(e) => {
+      eprintln!("ERROR loading '{}': {}", filename, e);
+      eprintln!("Using default maze instead");
+      default_level()
+    }
+  };
+  let report = analyze(&level, &markers);
+  if !report.is_ok() {
+    eprintln!("WARNING: problems in '{}' (run with --check for details):", filename);
+    eprint!("{}", report);
+  }
+  let placements = take_placements(&mut level.maze, archetypes);
+  (level, placements)
+}
+
+// Valor que sigue a una opción de la línea de comandos
+fn arg_value<'a>(args: &'a [String], flag: &str) -> Option<&'a str> {
+  let idx = args.iter().position(|a| a == flag)?;
+  args.get(idx + 1).map(String::as_str).filter(|v| !v.starts_with("--"))
+}
+
+// Semilla de la partida: --seed N o una al azar. Todo el azar del juego
+// (laberinto generado, spawns e IA) sale de un único StdRng con esta semilla.
+fn session_seed(args: &[String]) -> u64 {
+  match arg_value(args, "--seed").map(str::parse::<u64>) {
+    Some(Ok(seed)) => seed,
+    Some(Err(_)) => {
+      eprintln!("WARNING: bad --seed, expected a number; using a random one");
+      rand::thread_rng().gen()
+    }
+    None => rand::thread_rng().gen(),
+  }
+}
+
+// --generate [algoritmo]: laberinto procedural en vez de maze.txt, con
+// --size ANCHOxALTO (en celdas) y --supplies N
+fn generated_level(args: &[String], seed: u64, rng: &mut StdRng) -> Option<Level> {
+  if !args.iter().any(|a| a == "--generate") {
+    return None;
+  }
+  let mut options = MapGenOptions::default();
+  if let Some(name) = arg_value(args, "--generate") {
+    match Algorithm::parse(name) {
+      Some(algorithm) => options.algorithm = algorithm,
+      None => eprintln!("WARNING: unknown generator '{}', using {}", name, options.algorithm.name()),
+    }
+  }
+  if let Some(size) = arg_value(args, "--size") {
+    match size.split_once('x').and_then(|(w, h)| Some((w.parse().ok()?, h.parse().ok()?))) {
+      Some((w, h)) => (options.width, options.height) = (w, h),
+      None => eprintln!("WARNING: bad --size '{}', expected e.g. 20x12", size),
+    }
+  }
+  if let Some(n) = arg_value(args, "--supplies") {
+    match n.parse() {
+      Ok(n) => options.supplies = n,
+      Err(_) => eprintln!("WARNING: bad --supplies '{}'", n),
+    }
+  }
+
+  let name = format!("{} {}x{} (seed {})", options.algorithm.name(), options.width, options.height, seed);
+  println!("Generated maze: {}", name);
+  Some(Level::from_maze(&name, generate_with(&options, rng)))
+}
+
+// --check: valida un laberinto y analiza si todo es alcanzable, sin abrir
+// ventana. Devuelve `false` si encontró problemas.
+fn check_level(level: &Level, block_size: usize) -> bool {
+  let markers: Vec<char> = load_archetypes("zombies.txt", block_size).iter().filter_map(|a| a.marker).collect();
+
+  let problems = validate_maze(level, &markers);
+  for p in &problems {
+    println!("{}: {}", level.name, p);
+  }
+  let report = analyze(level, &markers);
+  print!("{}", report);
+  if let Some(route) = report.route_length {
+    let secs = route as f32 * block_size as f32 / player::MOVE_SPEED;
+    println!("walking the route takes about {:.0}s (time limit {}s)", secs, level.time_limit);
+  }
+
+  let ok = problems.is_empty() && report.is_ok();
+  println!("{}", if ok { "OK" } else { "FAILED" });
+  ok
+}
+
+fn level_player(level: &Level, block_size: usize) -> Player {
+  let (x, y) = level.player_pos(block_size);
+  Player::new(Vector2::new(x, y), level.player_facing, PI / 3.0)
+}
+
+fn level_game_data(level: &Level) -> GameData {
+  let mut game_data = GameData::new();
+  game_data.total_medical_supplies = count_medical_supplies(&level.maze);
+  game_data.time_limit = Duration::from_secs(level.time_limit);
+  game_data
+}
+
+// Renderiza la vista inicial a una imagen sin abrir ventana
+fn render_snapshot(path: &str, width: u32, height: u32, block_size: usize) {
+  let (level, _) = load_level_with_placements("maze.txt", &load_archetypes("zombies.txt", block_size));
+  let maze = level.maze.clone();
+  let floor_map = load_floor_map("floor.txt");
+  let texture_manager = TextureManager::new();
+  let player = level_player(&level, block_size);
+
+  let mut framebuffer = Framebuffer::new(width, height);
+  let mut zbuffer = Vec::new();
+  render_world(&mut framebuffer, &mut zbuffer, &Scene {
+    maze: &maze,
+    floor_map: floor_map.as_ref(),
+    block_size,
+    player: &player,
+    sprites: &maze_sprites(&maze, block_size, None),
+    textures: &texture_manager,
+  });
+  framebuffer.render_to_file(path);
+  println!("Snapshot written to '{}'", path);
+}
+
 fn main() {
   let window_width = 1300;
   let window_height = 900;
   let block_size = 80;
 
+  let args: Vec<String> = std::env::args().collect();
+  if let Some(idx) = args.iter().position(|a| a == "--snapshot") {
+    let path = args.get(idx + 1).map(String::as_str).unwrap_or("snapshot.png");
+    render_snapshot(path, window_width as u32, window_height as u32, block_size);
+    return;
+  }
+  let seed = session_seed(&args);
+  let mut rng = StdRng::seed_from_u64(seed);
+
+  if args.iter().any(|a| a == "--check") {
+    // Con --generate se revisa (y se muestra) el laberinto generado
+    let level = match generated_level(&args, seed, &mut rng) {
+      Some(level) => {
+        for row in &level.maze {
+          println!("{}", row.iter().collect::<String>());
+        }
+        level
+      }
+      None => {
+        let path = arg_value(&args, "--check").unwrap_or("maze.txt");
+        match std::fs::read_to_string(path) {
+          Ok(text) => maze::parse_level(path, &text),
+          Err(e) => {
+            eprintln!("ERROR: could not read '{}': {}", path, e);
+            std::process::exit(1);
+          }
+        }
+      }
+    };
+    let ok = check_level(&level, block_size);
+    std::process::exit(if ok { 0 } else { 1 });
+  }
+
   let (mut rl, thread) = raylib::init()
     .size(window_width, window_height)
     .title("Zombie Lab Escape - Hospital Edition")
@@ -490,47 +511,34 @@ fn main() {
       }
   };
 
-  let texture_manager = TextureManager::new(&mut rl, &thread);
+  let texture_manager = TextureManager::new();
+  let mut framebuffer = Framebuffer::new(window_width as u32, window_height as u32);
+  let mut zbuffer: Vec<f32> = Vec::new();
 
-  let mut maze = load_maze("maze.txt");
-  let mut game_data = GameData::new();
-  game_data.total_medical_supplies = count_medical_supplies(&maze);
-
-  let mut player = Player::new(
-    Vector2::new(120.0, 120.0),
-    PI / 4.0,
-    PI / 3.0,
-  );
-
-  let mut rng = rand::thread_rng();
-
-  let mut zombies: Vec<Zombie> = {
-      let desired = 10usize;
-      let mut zs = Vec::with_capacity(desired);
-      let mut tries = 0;
-      while zs.len() < desired && tries < 2000 {
-          tries += 1;
-          let j = rng.gen_range(0..maze.len());
-          let i = rng.gen_range(0..maze[0].len());
-          let c = maze[j][i];
-          if c == ' ' || c == 'M' || c == 'g' {
-              let x = i as f32 * block_size as f32 + (block_size as f32 * 0.5);
-              let y = j as f32 * block_size as f32 + (block_size as f32 * 0.5);
-              let dx = x - player.pos.x;
-              let dy = y - player.pos.y;
-              if dx*dx + dy*dy > (block_size as f32 * 4.0).powi(2) {
-                  zs.push(Zombie::new(Vector2::new(x, y)));
-              }
-          }
-      }
-      if zs.is_empty() {
-          vec![
-              Zombie::new(Vector2::new(200.0, 200.0)),
-              Zombie::new(Vector2::new(400.0, 300.0)),
-              Zombie::new(Vector2::new(600.0, 400.0)),
-          ]
-      } else { zs }
+  let floor_map = load_floor_map("floor.txt");
+  let archetypes = load_archetypes("zombies.txt", block_size);
+  println!("Seed: {} (replay with --seed {})", seed, seed);
+  let (level, placements) = match generated_level(&args, seed, &mut rng) {
+    Some(level) => (level, Vec::new()),
+    None => load_level_with_placements("maze.txt", &archetypes),
   };
+  let mut maze = level.maze.clone();
+  let mut game_data = level_game_data(&level);
+  let mut player = level_player(&level, block_size);
+  println!("Level: {}", level.name);
+
+  if let (Some(audio), Some(music)) = (audio_manager.as_mut(), level.music.as_deref()) {
+    audio.set_level_music(&mut rl, Some(music));
+  }
+
+  let mut bindings = Bindings::load("controls.txt");
+  let mut rebind_menu = RebindMenu::new();
+  let mut controls_return = GameState::MainMenu;
+
+  let mut nav_field = NavField::new();
+  let mut show_nav_debug = false;
+
+  let mut zombies = spawn_zombies(&level, block_size, &archetypes, &placements, player.pos, &mut rng);
 
   let mut frame_count = 0;
   let mut fps = 0;
@@ -568,9 +576,18 @@ fn main() {
           menu_sound_played = true;
         }
 
-        let start_pressed =
-          rl.is_key_pressed(KeyboardKey::KEY_ENTER) ||
-          rl.is_key_pressed(KeyboardKey::KEY_SPACE);
+        if bindings.is_pressed(&rl, Action::Controls) {
+          controls_return = GameState::MainMenu;
+          rebind_menu = RebindMenu::new();
+          game_data.game_state = GameState::Controls;
+          // Al cambiar de pantalla igual se dibuja un cuadro: raylib recién
+          // suelta la tecla en EndDrawing y el estado nuevo la volvería a ver
+          let mut d = rl.begin_drawing(&thread);
+          render_controls_screen_into(&mut d, &bindings, &rebind_menu, window_width);
+          continue;
+        }
+
+        let start_pressed = bindings.is_pressed(&rl, Action::Interact);
 
         if start_pressed {
           println!("Entering the abandoned hospital...");
@@ -582,24 +599,7 @@ fn main() {
           menu_sound_played = false;
 
           // Respawn zombies
-          zombies.clear();
-          let desired = 10usize;
-          let mut tries = 0;
-          while zombies.len() < desired && tries < 2000 {
-              tries += 1;
-              let j = rng.gen_range(0..maze.len());
-              let i = rng.gen_range(0..maze[0].len());
-              let c = maze[j][i];
-              if c == ' ' || c == 'M' || c == 'g' {
-                  let x = i as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                  let y = j as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                  let dx = x - player.pos.x;
-                  let dy = y - player.pos.y;
-                  if dx*dx + dy*dy > (block_size as f32 * 4.0).powi(2) {
-                      zombies.push(Zombie::new(Vector2::new(x, y)));
-                  }
-              }
-          }
+          zombies = spawn_zombies(&level, block_size, &archetypes, &placements, player.pos, &mut rng);
 
           if let Some(ref mut audio) = audio_manager {
             audio.play_background_music(&mut rl);
@@ -609,7 +609,7 @@ fn main() {
         }
 
         let millis = (rl.get_time() * 1000.0) as u128;
-        let blink_color = if (millis / 600) % 2 == 0 {
+        let blink_color = if (millis / 600).is_multiple_of(2) {
           Color::new(0, 255, 100, 255)
         } else {
           Color::new(0, 150, 50, 255)
@@ -617,7 +617,7 @@ fn main() {
 
         {
           let mut d = rl.begin_drawing(&thread);
-          render_main_menu_into(&mut d, window_width, window_height, blink_color);
+          render_main_menu_into(&mut d, &bindings, window_width, window_height, blink_color);
         }
       }
 
@@ -640,49 +640,72 @@ fn main() {
         let dt = rl.get_frame_time();       
         let time_s = rl.get_time() as f32;  
 
-        process_events(&mut player, &rl, &maze, block_size);
+        if bindings.is_pressed(&rl, Action::Pause) {
+            game_data.pause();
+            // Sin este cuadro la pausa vería la misma tecla y se reanudaría
+            let mut d = rl.begin_drawing(&thread);
+            render_pause_screen_into(&mut d, &bindings, window_width, window_height);
+            continue;
+        }
+
+        process_events(&mut player, &rl, &bindings, &maze, block_size);
 
+        if rl.is_key_pressed(KeyboardKey::KEY_F3) {
+            show_nav_debug = !show_nav_debug;
+        }
+
+        // El campo solo se recalcula cuando el jugador cambia de celda
+        if let Some(cell) = cell_at(player.pos, block_size) {
+            nav_field.update(&maze, cell);
+        }
+        // Un golpe solo cuenta al terminar la preparación del ataque
+        let mut touched = false;
         for z in &mut zombies {
-            z.update(&maze, block_size, player.pos, dt);
+            touched |= z.update(&maze, block_size, &nav_field, &player, dt, &mut rng);
+            if z.spotted_player {
+                if let (Some(audio), Some(path)) = (audio_manager.as_mut(), z.sound.as_deref()) {
+                    audio.play_zombie_sound(&mut rl, path);
+                }
+            }
         }
+        propagate_alerts(&mut zombies);
+        resolve_overlaps(&mut zombies, &maze, block_size);
 
         check_collisions_and_pickups(
             &player, &mut maze, &mut game_data, block_size, &mut audio_manager, &mut rl
         );
 
-        {
-            let lose_dist = 22.0_f32; 
-            let lose_dist2 = lose_dist * lose_dist;
-            let mut touched = false;
-            for z in &zombies {
-                let dx = z.pos.x - player.pos.x;
-                let dy = z.pos.y - player.pos.y;
-                if dx*dx + dy*dy <= lose_dist2 {
-                    touched = true;
-                    break;
-                }
-            }
-            if touched {
-                game_data.game_state = GameState::Defeat;
-                if let Some(ref mut audio) = audio_manager {
-                    audio.stop_background_music(&mut rl);
-                }
-                println!("A zombie got you!");
-                continue;
+        if touched {
+            game_data.game_state = GameState::Defeat;
+            if let Some(ref mut audio) = audio_manager {
+                audio.stop_background_music(&mut rl);
             }
+            println!("A zombie got you!");
+            continue;
         }
 
         let screen_w = rl.get_screen_width() as f32;
         let screen_h = rl.get_screen_height() as f32;
 
+        let mut sprites = maze_sprites(&maze, block_size, Some(time_s));
+        sprites.extend(zombies.iter().map(Zombie::sprite));
+
+        framebuffer.resize(screen_w as u32, screen_h as u32);
+        render_world(&mut framebuffer, &mut zbuffer, &Scene {
+            maze: &maze,
+            floor_map: floor_map.as_ref(),
+            block_size,
+            player: &player,
+            sprites: &sprites,
+            textures: &texture_manager,
+        });
+
         {
             let mut d = rl.begin_drawing(&thread);
 
-            render_world_optimized_into(
-                &mut d, screen_w, screen_h,
-                &maze, block_size, &player, &zombies, &texture_manager,
-                time_s,
-            );
+            framebuffer.render_into(&mut d, &thread);
+            let nav_debug = if show_nav_debug { Some(&nav_field) } else { None };
+            render_minimap_direct(&mut d, &maze, &player, &zombies, nav_debug, block_size, screen_w as i32);
 
             let fps_color = if fps >= 15 { Color::new(0, 255, 100, 255) } else { Color::RED };
             d.draw_rectangle(10, 10, 140, 25, Color::new(0, 0, 0, 200));
@@ -713,6 +736,30 @@ fn main() {
         } 
       }
 
+      GameState::Paused => {
+        if bindings.is_pressed(&rl, Action::Pause) || bindings.is_pressed(&rl, Action::Interact) {
+            game_data.resume();
+        } else if bindings.is_pressed(&rl, Action::Controls) {
+            controls_return = GameState::Paused;
+            rebind_menu = RebindMenu::new();
+            game_data.game_state = GameState::Controls;
+        }
+
+        // También al salir de la pausa: el cuadro consume la tecla
+        let mut d = rl.begin_drawing(&thread);
+        render_pause_screen_into(&mut d, &bindings, window_width, window_height);
+      }
+
+      GameState::Controls => {
+        if rebind_menu.update(&mut rl, &mut bindings) {
+            bindings.save("controls.txt");
+            game_data.game_state = controls_return.clone();
+        }
+
+        let mut d = rl.begin_drawing(&thread);
+        render_controls_screen_into(&mut d, &bindings, &rebind_menu, window_width);
+      }
+
       GameState::Defeat => {
         if !defeat_sound_played {
             if let Some(ref mut audio) = audio_manager {
@@ -729,30 +776,13 @@ fn main() {
             victory_sound_played = false;
             menu_sound_played = false;
 
-            maze = load_maze("maze.txt");
-            game_data = GameData::new();
-            game_data.total_medical_supplies = count_medical_supplies(&maze);
-            player = Player::new(Vector2::new(120.0, 120.0), std::f32::consts::PI / 4.0, std::f32::consts::PI / 3.0);
-
-            // respawn aleatorio
-            zombies.clear();
-            let desired = 10usize;
-            let mut tries = 0;
-            while zombies.len() < desired && tries < 2000 {
-                tries += 1;
-                let j = rng.gen_range(0..maze.len());
-                let i = rng.gen_range(0..maze[0].len());
-                let c = maze[j][i];
-                if c == ' ' || c == 'M' || c == 'g' {
-                    let x = i as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                    let y = j as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                    let dx = x - player.pos.x;
-                    let dy = y - player.pos.y;
-                    if dx*dx + dy*dy > (block_size as f32 * 4.0).powi(2) {
-                        zombies.push(Zombie::new(Vector2::new(x, y)));
-                    }
-                }
-            }
+            // Se reinicia desde una copia del nivel cargado o generado
+            maze = level.maze.clone();
+            game_data = level_game_data(&level);
+            player = level_player(&level, block_size);
+
+            // respawn
+            zombies = spawn_zombies(&level, block_size, &archetypes, &placements, player.pos, &mut rng);
 
             game_data.game_state = GameState::MainMenu;
         }
@@ -777,30 +807,13 @@ fn main() {
             victory_sound_played = false;
             menu_sound_played = false;
 
-            maze = load_maze("maze.txt");
-            game_data = GameData::new();
-            game_data.total_medical_supplies = count_medical_supplies(&maze);
-            player = Player::new(Vector2::new(120.0, 120.0), std::f32::consts::PI / 4.0, std::f32::consts::PI / 3.0);
-
-            // respawn aleatorio
-            zombies.clear();
-            let desired = 10usize;
-            let mut tries = 0;
-            while zombies.len() < desired && tries < 2000 {
-                tries += 1;
-                let j = rng.gen_range(0..maze.len());
-                let i = rng.gen_range(0..maze[0].len());
-                let c = maze[j][i];
-                if c == ' ' || c == 'M' || c == 'g' {
-                    let x = i as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                    let y = j as f32 * block_size as f32 + (block_size as f32 * 0.5);
-                    let dx = x - player.pos.x;
-                    let dy = y - player.pos.y;
-                    if dx*dx + dy*dy > (block_size as f32 * 4.0).powi(2) {
-                        zombies.push(Zombie::new(Vector2::new(x, y)));
-                    }
-                }
-            }
+            // Se reinicia desde una copia del nivel cargado o generado
+            maze = level.maze.clone();
+            game_data = level_game_data(&level);
+            player = level_player(&level, block_size);
+
+            // respawn
+            zombies = spawn_zombies(&level, block_size, &archetypes, &placements, player.pos, &mut rng);
 
             game_data.game_state = GameState::MainMenu;
           }
@@ -0,0 +1,350 @@
+// mapgen.rs
+// Laberintos de hospital generados al azar, con el mismo dibujo que maze.txt:
+// cada celda ocupa 3 columnas y 2 filas (`+--+` arriba, `|  ` al costado).
+// La misma semilla da siempre el mismo laberinto.
+
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::VecDeque;
+use crate::maze::Maze;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    // Pasillos largos y retorcidos
+    Backtracker,
+    // Muchos callejones cortos
+    Prim,
+    // Salas rectangulares unidas por pasillos
+    Rooms,
+}
+
+impl Algorithm {
+    pub const ALL: [Algorithm; 3] = [Algorithm::Backtracker, Algorithm::Prim, Algorithm::Rooms];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Algorithm::Backtracker => "backtracker",
+            Algorithm::Prim => "prim",
+            Algorithm::Rooms => "rooms",
+        }
+    }
+
+    pub fn parse(name: &str) -> Option<Algorithm> {
+        Algorithm::ALL.into_iter().find(|a| a.name() == name)
+    }
+}
+
+pub struct MapGenOptions {
+    pub algorithm: Algorithm,
+    // Tamaño en celdas; la grilla queda de (3 * width + 1) x (2 * height + 1)
+    pub width: usize,
+    pub height: usize,
+    pub supplies: usize,
+    // Celdas de camino entre el inicio, la salida y cada suministro. Si no
+    // caben todos se acepta la mayor distancia posible.
+    pub min_distance: usize,
+}
+
+impl Default for MapGenOptions {
+    fn default() -> Self {
+        MapGenOptions {
+            algorithm: Algorithm::Backtracker,
+            width: 20,
+            height: 12,
+            supplies: 5,
+            min_distance: 6,
+        }
+    }
+}
+
+// Celdas y qué paredes entre vecinas están abiertas
+struct Grid {
+    width: usize,
+    height: usize,
+    open_east: Vec<bool>,
+    open_south: Vec<bool>,
+}
+
+impl Grid {
+    fn new(width: usize, height: usize) -> Self {
+        Grid {
+            width,
+            height,
+            open_east: vec![false; width * height],
+            open_south: vec![false; width * height],
+        }
+    }
+
+    fn neighbors(&self, c: usize) -> Vec<usize> {
+        let (x, y) = (c % self.width, c / self.width);
+        let mut out = Vec::with_capacity(4);
+        if x > 0 { out.push(c - 1); }
+        if x + 1 < self.width { out.push(c + 1); }
+        if y > 0 { out.push(c - self.width); }
+        if y + 1 < self.height { out.push(c + self.width); }
+        out
+    }
+
+    fn open(&mut self, a: usize, b: usize) {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 {
+            self.open_east[a] = true;
+        } else {
+            self.open_south[a] = true;
+        }
+    }
+
+    fn is_open(&self, a: usize, b: usize) -> bool {
+        let (a, b) = (a.min(b), a.max(b));
+        if b == a + 1 { self.open_east[a] } else { self.open_south[a] }
+    }
+
+    // Pasos por el laberinto desde `start` a cada celda
+    fn distances(&self, start: usize) -> Vec<Option<usize>> {
+        let mut dist = vec![None; self.width * self.height];
+        let mut queue = VecDeque::new();
+        dist[start] = Some(0);
+        queue.push_back(start);
+        while let Some(c) = queue.pop_front() {
+            let d = dist[c].unwrap_or(0);
+            for n in self.neighbors(c) {
+                if self.is_open(c, n) && dist[n].is_none() {
+                    dist[n] = Some(d + 1);
+                    queue.push_back(n);
+                }
+            }
+        }
+        dist
+    }
+
+    // Pared arriba de la celda (x, y); y == height es el borde de abajo
+    fn wall_above(&self, x: usize, y: usize) -> bool {
+        y == 0 || y == self.height || !self.open_south[(y - 1) * self.width + x]
+    }
+
+    // Pared a la izquierda de la celda (x, y); x == width es el borde derecho
+    fn wall_left(&self, x: usize, y: usize) -> bool {
+        x == 0 || x == self.width || !self.open_east[y * self.width + x - 1]
+    }
+}
+
+fn carve_backtracker(grid: &mut Grid, rng: &mut impl Rng) {
+    let mut visited = vec![false; grid.width * grid.height];
+    let mut stack = vec![0];
+    visited[0] = true;
+    while let Some(&c) = stack.last() {
+        let next: Vec<usize> = grid.neighbors(c).into_iter().filter(|&n| !visited[n]).collect();
+        match next.choose(rng) {
+            Some(&n) => {
+                grid.open(c, n);
+                visited[n] = true;
+                stack.push(n);
+            }
+            None => { stack.pop(); }
+        }
+    }
+}
+
+fn carve_prim(grid: &mut Grid, rng: &mut impl Rng) {
+    let mut visited = vec![false; grid.width * grid.height];
+    // Paredes (de, hacia) entre lo ya tallado y lo que falta
+    let mut frontier: Vec<(usize, usize)> = grid.neighbors(0).into_iter().map(|n| (0, n)).collect();
+    visited[0] = true;
+    while !frontier.is_empty() {
+        let (from, to) = frontier.swap_remove(rng.gen_range(0..frontier.len()));
+        if visited[to] { continue; }
+        grid.open(from, to);
+        visited[to] = true;
+        frontier.extend(grid.neighbors(to).into_iter().filter(|&n| !visited[n]).map(|n| (to, n)));
+    }
+}
+
+fn find(parent: &mut [usize], mut c: usize) -> usize {
+    while parent[c] != c {
+        parent[c] = parent[parent[c]];
+        c = parent[c];
+    }
+    c
+}
+
+// Salas sin paredes internas y después pasillos al azar (Kruskal) hasta que
+// todo quede conectado
+fn carve_rooms(grid: &mut Grid, rng: &mut impl Rng) {
+    let (w, h) = (grid.width, grid.height);
+    let mut parent: Vec<usize> = (0..w * h).collect();
+    let mut rooms: Vec<(usize, usize, usize, usize)> = Vec::new();
+
+    for _ in 0..(w * h / 8).max(1) {
+        let (rw, rh) = (rng.gen_range(2..=4).min(w), rng.gen_range(2..=3).min(h));
+        let (rx, ry) = (rng.gen_range(0..=w - rw), rng.gen_range(0..=h - rh));
+        // Deja al menos una celda de pasillo entre salas
+        let overlaps = rooms.iter().any(|&(ox, oy, ow, oh)| {
+            rx <= ox + ow && ox <= rx + rw && ry <= oy + oh && oy <= ry + rh
+        });
+        if overlaps { continue; }
+        rooms.push((rx, ry, rw, rh));
+        for y in ry..ry + rh {
+            for x in rx..rx + rw {
+                let c = y * w + x;
+                if x + 1 < rx + rw { grid.open(c, c + 1); }
+                if y + 1 < ry + rh { grid.open(c, c + w); }
+                let root = find(&mut parent, c);
+                let first = find(&mut parent, ry * w + rx);
+                parent[root] = first;
+            }
+        }
+    }
+
+    let mut walls: Vec<(usize, usize)> = (0..w * h)
+        .flat_map(|c| {
+            let mut pair = Vec::with_capacity(2);
+            if c % w + 1 < w { pair.push((c, c + 1)); }
+            if c / w + 1 < h { pair.push((c, c + w)); }
+            pair
+        })
+        .collect();
+    walls.shuffle(rng);
+    for (a, b) in walls {
+        let (ra, rb) = (find(&mut parent, a), find(&mut parent, b));
+        if ra != rb {
+            grid.open(a, b);
+            parent[ra] = rb;
+        }
+    }
+}
+
+/// Celdas donde van la salida (primera) y los suministros. Cada una queda a
+/// `min_distance` pasos del inicio y de las anteriores; si no hay lugar, se
+/// va bajando la distancia.
+fn place_items(grid: &Grid, count: usize, min_distance: usize, rng: &mut impl Rng) -> Vec<usize> {
+    let mut free: Vec<usize> = (1..grid.width * grid.height).collect();
+    free.shuffle(rng);
+    let mut dists = vec![grid.distances(0)];
+    let mut placed = Vec::new();
+
+    for _ in 0..count {
+        let mut required = min_distance;
+        let pick = loop {
+            let far_enough = |c: &usize| dists.iter().all(|d| d[*c].is_some_and(|d| d >= required));
+            match free.iter().position(far_enough) {
+                Some(k) => break Some(k),
+                None if required == 0 => break None,
+                None => required -= 1,
+            }
+        };
+        let Some(k) = pick else { break; };
+        let c = free.remove(k);
+        dists.push(grid.distances(c));
+        placed.push(c);
+    }
+    placed
+}
+
+fn draw(grid: &Grid, exit: Option<usize>, supplies: &[usize]) -> Maze {
+    let (w, h) = (grid.width, grid.height);
+    let mut maze: Maze = vec![vec![' '; 3 * w + 1]; 2 * h + 1];
+
+    for y in 0..=h {
+        for x in 0..=w {
+            let horizontal = (x > 0 && grid.wall_above(x - 1, y)) || (x < w && grid.wall_above(x, y));
+            let vertical = (y > 0 && grid.wall_left(x, y - 1)) || (y < h && grid.wall_left(x, y));
+            maze[2 * y][3 * x] = match (horizontal, vertical) {
+                (true, _) => '+',
+                (false, true) => '|',
+                (false, false) => ' ',
+            };
+            if x < w && grid.wall_above(x, y) {
+                maze[2 * y][3 * x + 1] = '-';
+                maze[2 * y][3 * x + 2] = '-';
+            }
+            if y < h && grid.wall_left(x, y) {
+                maze[2 * y + 1][3 * x] = '|';
+            }
+        }
+    }
+
+    let mut put = |c: usize, ch: char| maze[2 * (c / w) + 1][3 * (c % w) + 1] = ch;
+    if let Some(e) = exit {
+        put(e, 'g');
+    }
+    for &s in supplies {
+        put(s, 'M');
+    }
+    maze
+}
+
+/// Genera un laberinto. El jugador empieza en la celda de arriba a la
+/// izquierda, que en la grilla es (1, 1), el inicio por defecto de `Level`.
+pub fn generate(options: &MapGenOptions, seed: u64) -> Maze {
+    generate_with(options, &mut StdRng::seed_from_u64(seed))
+}
+
+// Igual que `generate`, sacando el azar del generador de la partida
+pub fn generate_with(options: &MapGenOptions, rng: &mut impl Rng) -> Maze {
+    let mut grid = Grid::new(options.width.max(2), options.height.max(2));
+    match options.algorithm {
+        Algorithm::Backtracker => carve_backtracker(&mut grid, rng),
+        Algorithm::Prim => carve_prim(&mut grid, rng),
+        Algorithm::Rooms => carve_rooms(&mut grid, rng),
+    }
+    let items = place_items(&grid, options.supplies + 1, options.min_distance, rng);
+    draw(&grid, items.first().copied(), items.get(1..).unwrap_or(&[]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::analysis::analyze;
+    use crate::maze::{validate_maze, Level};
+
+    fn options(algorithm: Algorithm) -> MapGenOptions {
+        MapGenOptions { algorithm, ..MapGenOptions::default() }
+    }
+
+    #[test]
+    fn same_seed_same_maze() {
+        for algorithm in Algorithm::ALL {
+            let opts = options(algorithm);
+            assert_eq!(generate(&opts, 42), generate(&opts, 42));
+            assert_ne!(generate(&opts, 42), generate(&opts, 43), "{}", algorithm.name());
+            // Desde el generador de la partida da lo mismo que con la semilla
+            assert_eq!(generate_with(&opts, &mut StdRng::seed_from_u64(42)), generate(&opts, 42));
+        }
+    }
+
+    #[test]
+    fn every_algorithm_gives_a_valid_playable_maze() {
+        for algorithm in Algorithm::ALL {
+            for seed in 0..10 {
+                let level = Level::from_maze("generated", generate(&options(algorithm), seed));
+                assert_eq!(level.maze.len(), 25);
+                assert!(validate_maze(&level, &[]).is_empty(), "{} seed {}", algorithm.name(), seed);
+                let report = analyze(&level, &[]);
+                assert!(report.is_ok(), "{} seed {}:\n{}", algorithm.name(), seed, report);
+                assert_eq!(report.supplies.len(), 5);
+            }
+        }
+    }
+
+    #[test]
+    fn items_keep_their_distance() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut grid = Grid::new(12, 8);
+        carve_backtracker(&mut grid, &mut rng);
+        let items = place_items(&grid, 4, 5, &mut rng);
+        assert_eq!(items.len(), 4);
+        for (k, &a) in items.iter().enumerate() {
+            let dist = grid.distances(a);
+            assert!(dist[0].unwrap() >= 5);
+            for &b in &items[k + 1..] {
+                assert!(dist[b].unwrap() >= 5);
+            }
+        }
+
+        // En un laberinto chico no caben: igual se colocan todos
+        let mut grid = Grid::new(2, 2);
+        carve_prim(&mut grid, &mut rng);
+        assert_eq!(place_items(&grid, 3, 10, &mut rng).len(), 3);
+    }
+}
@@ -1,70 +1,356 @@
 // maze.rs 
 
-use std::fs::File;
-use std::io::{BufRead, BufReader};
 use std::path::Path;
+use crate::tiles::{is_player_walkable, tile, tiles, Pickup};
 
 pub type Maze = Vec<Vec<char>>;
 
-pub fn load_maze(filename: &str) -> Maze {
-    if !Path::new(filename).exists() {
-        eprintln!("WARNING: File '{}' not found, creating default maze", filename);
-        return create_default_maze();
-    }
-
-    match File::open(filename) {
-        Ok(file) => {
-            let reader = BufReader::new(file);
-            let maze: Maze = reader
-                .lines()
-                .map(|line| line.unwrap_or_default().chars().collect())
-                .collect();
-            
-            if maze.is_empty() {
-                eprintln!("WARNING: Empty maze file, using default maze");
-                create_default_maze()
-            } else {
-                println!("Successfully loaded maze from '{}'", filename);
-                maze
+// Valores del nivel cuando la cabecera no los da
+pub const DEFAULT_TIME_LIMIT: u64 = 5 * 60;
+pub const DEFAULT_ZOMBIE_COUNT: usize = 10;
+// Sin 'P' el jugador empieza en la celda (1, 1) mirando en diagonal
+const DEFAULT_START: (usize, usize) = (1, 1);
+const DEFAULT_FACING: f32 = std::f32::consts::FRAC_PI_4;
+// Separa la cabecera opcional de la grilla
+const HEADER_END: &str = "---";
+// Marcadores de la grilla: inicio del jugador y puntos de spawn de zombies
+const PLAYER_MARKER: char = 'P';
+const SPAWN_MARKER: char = '*';
+
+/// Un laberinto con sus datos. Un archivo puede empezar con una cabecera
+/// `clave = valor` terminada en `---`:
+///
+/// ```text
+/// name = Ala de cirugía
+/// time_limit = 240
+/// zombies = 8
+/// music = sounds/otra.mp3
+/// facing = east
+/// legend X = #
+/// ---
+/// ```
+///
+/// `time_limit` va en segundos, `zombies` es la cantidad que aparece al azar,
+/// `facing` es hacia dónde mira 'P' (east/south/west/north o grados) y cada
+/// `legend` hace que un carácter de la grilla se lea como otro. En la grilla,
+/// 'P' marca el inicio del jugador y '*' los puntos de spawn de zombies.
+///
+/// Sin `---` todo el archivo es grilla, como siempre.
+pub struct Level {
+    pub name: String,
+    pub maze: Maze,
+    pub time_limit: u64,
+    pub zombie_count: usize,
+    pub music: Option<String>,
+    // Celda (columna, fila) y dirección inicial del jugador
+    pub player_start: (usize, usize),
+    pub player_facing: f32,
+    pub spawn_points: Vec<(usize, usize)>,
+    // 'P' de más: vale el primero y `validate_maze` reporta los otros
+    pub extra_starts: Vec<(usize, usize)>,
+    // Líneas del archivo antes de la grilla (cabecera y '---'), para que los
+    // errores citen la línea real
+    pub grid_line: usize,
+}
+
+impl Level {
+    pub fn from_maze(name: &str, maze: Maze) -> Level {
+        Level {
+            name: name.to_string(),
+            maze,
+            time_limit: DEFAULT_TIME_LIMIT,
+            zombie_count: DEFAULT_ZOMBIE_COUNT,
+            music: None,
+            player_start: DEFAULT_START,
+            player_facing: DEFAULT_FACING,
+            spawn_points: Vec::new(),
+            extra_starts: Vec::new(),
+            grid_line: 0,
+        }
+    }
+
+    // Centro de la celda de inicio, en unidades del mundo
+    pub fn player_pos(&self, block_size: usize) -> (f32, f32) {
+        let bs = block_size as f32;
+        let (i, j) = self.player_start;
+        ((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs)
+    }
+}
+
+fn parse_facing(value: &str) -> Option<f32> {
+    match value {
+        "east" => Some(0.0),
+        "south" => Some(std::f32::consts::FRAC_PI_2),
+        "west" => Some(std::f32::consts::PI),
+        "north" => Some(-std::f32::consts::FRAC_PI_2),
+        degrees => degrees.parse::<f32>().ok().map(f32::to_radians),
+    }
+}
+
+pub fn parse_level(name: &str, text: &str) -> Level {
+    let lines: Vec<&str> = text.lines().collect();
+    let (header, grid) = match lines.iter().position(|l| l.trim_end() == HEADER_END) {
+        Some(idx) => (&lines[..idx], &lines[idx + 1..]),
+        None => (&lines[..0], &lines[..]),
+    };
+
+    let mut level = Level::from_maze(name, Vec::new());
+    level.grid_line = lines.len() - grid.len();
+    let mut legend: Vec<(char, char)> = Vec::new();
+
+    for raw in header {
+        // Solo líneas enteras de comentario: '#' es un valor válido en `legend`
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') { continue; }
+        let Some((key, value)) = line.split_once('=') else {
+            eprintln!("WARNING: ignoring header line '{}' in '{}'", line, name);
+            continue;
+        };
+        let (key, value) = (key.trim(), value.trim());
+
+        match key {
+            "name" => level.name = value.to_string(),
+            "time_limit" => match value.parse() {
+                Ok(secs) => level.time_limit = secs,
+                Err(_) => eprintln!("WARNING: bad time_limit '{}' in '{}'", value, name),
+            },
+            "zombies" => match value.parse() {
+                Ok(n) => level.zombie_count = n,
+                Err(_) => eprintln!("WARNING: bad zombies '{}' in '{}'", value, name),
+            },
+            "music" => level.music = Some(value.to_string()),
+            "facing" => match parse_facing(value) {
+                Some(a) => level.player_facing = a,
+                None => eprintln!("WARNING: bad facing '{}' in '{}'", value, name),
+            },
+            _ => {
+                let from = key.strip_prefix("legend").map(str::trim);
+                let mut from_chars = from.unwrap_or("").chars();
+                let mut to_chars = value.chars();
+                match (from_chars.next(), from_chars.next(), to_chars.next(), to_chars.next()) {
+                    (Some(f), None, Some(t), None) if from.is_some() => legend.push((f, t)),
+                    _ => eprintln!("WARNING: unknown header line '{}' in '{}'", line, name),
+                }
+            }
+        }
+    }
+
+    let mut start = None;
+    for (j, line) in grid.iter().enumerate() {
+        let mut row: Vec<char> = Vec::with_capacity(line.len());
+        for (i, c) in line.chars().enumerate() {
+            let c = legend.iter().find(|&&(f, _)| f == c).map_or(c, |&(_, t)| t);
+            match c {
+                PLAYER_MARKER => {
+                    match start {
+                        None => start = Some((i, j)),
+                        Some(_) => level.extra_starts.push((i, j)),
+                    }
+                    row.push(' ');
+                }
+                SPAWN_MARKER => {
+                    level.spawn_points.push((i, j));
+                    row.push(' ');
+                }
+                c => row.push(c),
+            }
+        }
+        level.maze.push(row);
+    }
+    if let Some(cell) = start {
+        level.player_start = cell;
+    }
+    level
+}
+
+#[derive(Debug)]
+pub enum MazeError {
+    MissingFile(String),
+    Io(String, std::io::Error),
+    EmptyFile,
+    // `line` es la línea del archivo (desde 1, contando la cabecera) y `col`
+    // la columna (desde 0). Ancho de la fila y ancho de la primera fila
+    RaggedRow { line: usize, width: usize, expected: usize },
+    UnknownChar { line: usize, col: usize, ch: char },
+    // Celda caminable en el borde: por ahí se sale del mapa
+    HoleInBorder { line: usize, col: usize },
+    NoExit,
+    NoPlayerStart,
+    // Un 'P' después del primero
+    DuplicatePlayerStart { line: usize, col: usize },
+}
+
+impl std::fmt::Display for MazeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            MazeError::MissingFile(path) => write!(f, "file '{}' not found", path),
+            MazeError::Io(path, e) => write!(f, "could not read '{}': {}", path, e),
+            MazeError::EmptyFile => write!(f, "the maze has no rows"),
+            MazeError::RaggedRow { line, width, expected } => {
+                write!(f, "line {}: row is {} chars wide, expected {}", line, width, expected)
+            }
+            MazeError::UnknownChar { line, col, ch } => {
+                write!(f, "line {}, column {}: unknown tile '{}'", line, col + 1, ch)
+            }
+            MazeError::HoleInBorder { line, col } => {
+                write!(f, "line {}, column {}: hole in the outer wall", line, col + 1)
+            }
+            MazeError::NoExit => write!(f, "the maze has no exit ('g')"),
+            MazeError::NoPlayerStart => write!(f, "the player start is not on a walkable cell"),
+            MazeError::DuplicatePlayerStart { line, col } => {
+                write!(f, "line {}, column {}: more than one player start ('P')", line, col + 1)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MazeError {}
+
+/// Todos los problemas del nivel, en orden de lectura. `markers` son
+/// caracteres extra que el llamador quita de la grilla (arquetipos de zombie).
+pub fn validate_maze(level: &Level, markers: &[char]) -> Vec<MazeError> {
+    let maze = &level.maze;
+    let mut problems = Vec::new();
+    if maze.iter().all(|row| row.is_empty()) {
+        problems.push(MazeError::EmptyFile);
+        return problems;
+    }
+
+    let expected = maze[0].len();
+    let last = maze.len() - 1;
+    let mut has_exit = false;
+    let line_of = |j: usize| level.grid_line + j + 1;
+    for (j, row) in maze.iter().enumerate() {
+        if row.len() != expected {
+            problems.push(MazeError::RaggedRow { line: line_of(j), width: row.len(), expected });
+        }
+        for (i, &ch) in row.iter().enumerate() {
+            if markers.contains(&ch) { continue; }
+            if !tiles().iter().any(|t| t.ch == ch) {
+                problems.push(MazeError::UnknownChar { line: line_of(j), col: i, ch });
+                continue;
             }
-        },
-        Err(e) => {
-            eprintln!("ERROR loading '{}': {}", filename, e);
-            eprintln!("Using default maze instead");
-            create_default_maze()
+            let on_border = j == 0 || j == last || i == 0 || i + 1 == row.len();
+            if on_border && is_player_walkable(ch) {
+                problems.push(MazeError::HoleInBorder { line: line_of(j), col: i });
+            }
+            has_exit |= tile(ch).pickup == Some(Pickup::Exit);
+        }
+    }
+
+    for &(col, row) in &level.extra_starts {
+        problems.push(MazeError::DuplicatePlayerStart { line: line_of(row), col });
+    }
+    if !has_exit {
+        problems.push(MazeError::NoExit);
+    }
+    let (si, sj) = level.player_start;
+    if !maze.get(sj).and_then(|row| row.get(si)).is_some_and(|&c| is_player_walkable(c)) {
+        problems.push(MazeError::NoPlayerStart);
+    }
+    problems
+}
+
+/// Lee y valida un nivel; si hay problemas devuelve el primero
+/// (`validate_maze` los da todos).
+pub fn load_level(filename: &str, markers: &[char]) -> Result<Level, MazeError> {
+    if !Path::new(filename).exists() {
+        return Err(MazeError::MissingFile(filename.to_string()));
+    }
+    let text = std::fs::read_to_string(filename).map_err(|e| MazeError::Io(filename.to_string(), e))?;
+    let level = parse_level(filename, &text);
+    match validate_maze(&level, markers).into_iter().next() {
+        Some(problem) => Err(problem),
+        None => {
+            println!("Successfully loaded maze from '{}'", filename);
+            Ok(level)
         }
     }
 }
 
-fn create_default_maze() -> Maze {
-    let maze_str = r#"+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+
-|                                                                 |
-|  +--+--+  +--+--+--+  +--+--+--+  +--+--+--+  +--+--+--+--+  |
-|  |     |  |        |  |        |  |        |  |              |
-|  |  M  |  |  +--+  +--+  +--+  +--+  +--+  |  |  +--+--+--+  |
-|  |     |  |  |     |     |  |     |  |  |  |  |  |           |
-|  +--+--+  |  |  +--+  +--+  |  M  |  +--+  |  +--+  +--+--+  |
-|           |  |  |     |     |     |        |        |        |
-|  +--+--+--+  |  +--+  |  +--+--+  +--+--+--+--+--+  |  +--+  |
-|  |        |  |        |                             |  |  |  |
-|  |  +--+  |  +--+--+--+--+--+--+  +--+--+--+--+  +--+  |  |  |
-|  |  |  |  |                    |  |           |  |     |  |  |
-|  +--+  |  +--+  +--+--+--+--+  |  |  +--+--+  |  |  +--+  |  |
-|        |        |           |  |  |  |     |  |  |  |     |  |
-|  +--+--+--+--+  |  M  +--+  |  |  +--+  +--+  |  +--+  +--+  |
-|  |           |  |     |  |  |  |        |     |        |     |
-|  |  +--+--+  |  +--+--+  |  |  +--+--+--+  +--+--+--+--+  +--+
-|  |        |  |           |  |                             |   |
-|  +--+--+  |  +--+--+--+--+  +--+--+--+--+--+--+--+--+--+  |  |
-|        |  |                                                |  |
-+--+--+  |  +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+  |
-|        |                                                      |
-|  +--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+  |
-|                                                               g|
-+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+--+"#;
-
-    maze_str
-        .lines()
-        .map(|line| line.chars().collect())
-        .collect()
-}
\ No newline at end of file
+pub fn load_maze(filename: &str) -> Result<Maze, MazeError> {
+    load_level(filename, &[]).map(|level| level.maze)
+}
+
+// Copia del maze.txt del repo, para cuando el archivo falta o está roto
+pub fn default_level() -> Level {
+    parse_level("built-in maze", include_str!("../maze.txt"))
+}
+
+// Mapa de suelos opcional: misma grilla que el laberinto, cada carácter elige
+// la textura de suelo de esa celda (' ' o '.' = suelo normal)
+pub fn load_floor_map(filename: &str) -> Option<Maze> {
+    let text = std::fs::read_to_string(filename).ok()?;
+    let floor_map: Maze = text.lines().map(|line| line.chars().collect()).collect();
+    println!("Loaded floor map from '{}'", filename);
+    Some(floor_map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_files_are_all_grid() {
+        let level = parse_level("plain", "+--+\n|  |\n+--+\n");
+        assert_eq!(level.maze.len(), 3);
+        assert_eq!(level.player_start, DEFAULT_START);
+        assert_eq!(level.time_limit, DEFAULT_TIME_LIMIT);
+    }
+
+    #[test]
+    fn header_sets_metadata_legend_and_markers() {
+        let text = "name = Quirófano\n# comentario\ntime_limit = 90\nzombies = 3\nmusic = sounds/x.mp3\nfacing = south\nlegend X = #\n---\nXXXXX\n#P *#\nXXXXX\n";
+        let level = parse_level("file", text);
+        assert_eq!(level.name, "Quirófano");
+        assert_eq!((level.time_limit, level.zombie_count), (90, 3));
+        assert_eq!(level.music.as_deref(), Some("sounds/x.mp3"));
+        assert_eq!(level.player_start, (1, 1));
+        assert!((level.player_facing - std::f32::consts::FRAC_PI_2).abs() < 1e-6);
+        assert_eq!(level.spawn_points, vec![(3, 1)]);
+        assert_eq!(level.maze[0], vec!['#'; 5]);
+        assert_eq!(level.maze[1], vec!['#', ' ', ' ', ' ', '#']);
+    }
+
+    #[test]
+    fn bundled_maze_is_valid() {
+        let problems = validate_maze(&default_level(), &[]);
+        assert!(problems.is_empty(), "{:?}", problems);
+    }
+
+    #[test]
+    fn validation_reports_every_problem() {
+        let level = parse_level("bad", "+--+\n|  \n| X|\n+ -+-\n");
+        let found: Vec<String> = validate_maze(&level, &[]).iter().map(|e| e.to_string()).collect();
+        assert_eq!(found, vec![
+            "line 2: row is 3 chars wide, expected 4",
+            "line 2, column 3: hole in the outer wall",
+            "line 3, column 3: unknown tile 'X'",
+            "line 4: row is 5 chars wide, expected 4",
+            "line 4, column 2: hole in the outer wall",
+            "the maze has no exit ('g')",
+        ]);
+
+        // 'Z' lo quita el llamador; el inicio por defecto cae en un muro
+        let level = parse_level("walled in", "+---+\n|#gZ|\n+---+\n");
+        assert!(matches!(validate_maze(&level, &['Z'])[..], [MazeError::NoPlayerStart]));
+        assert!(matches!(validate_maze(&parse_level("empty", ""), &[])[..], [MazeError::EmptyFile]));
+
+        // Con dos 'P' vale el primero y el otro se reporta
+        let level = parse_level("two starts", "+----+
+|P gP|
++----+
+");
+        assert_eq!(level.player_start, (1, 1));
+        let found: Vec<String> = validate_maze(&level, &[]).iter().map(|e| e.to_string()).collect();
+        assert_eq!(found, vec!["line 2, column 5: more than one player start ('P')"]);
+    }
+
+    #[test]
+    fn errors_count_the_header_lines() {
+        let level = parse_level("header", "name = x\n# comentario\n---\n+---+\n|PgX|\n+---+\n");
+        assert_eq!(level.grid_line, 3);
+        let found: Vec<String> = validate_maze(&level, &[]).iter().map(|e| e.to_string()).collect();
+        assert_eq!(found, vec!["line 5, column 4: unknown tile 'X'"]);
+    }
+}
@@ -0,0 +1,220 @@
+// nav.rs
+// Búsqueda de caminos sobre la grilla del laberinto (4 vecinos): A* para un
+// camino puntual y NavField, un campo de distancias compartido por todos.
+
+use raylib::prelude::Vector2;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use crate::tiles::is_zombie_walkable;
+
+// (columna, fila) en celdas
+pub type Cell = (usize, usize);
+
+// Celda que contiene un punto del mundo
+pub fn cell_at(p: Vector2, block_size: usize) -> Option<Cell> {
+    if p.x < 0.0 || p.y < 0.0 { return None; }
+    let bs = block_size as f32;
+    Some(((p.x / bs) as usize, (p.y / bs) as usize))
+}
+
+pub fn is_walkable_cell(maze: &[Vec<char>], (i, j): Cell) -> bool {
+    maze.get(j).and_then(|row| row.get(i)).is_some_and(|&c| is_zombie_walkable(c))
+}
+
+fn manhattan(a: Cell, b: Cell) -> usize {
+    a.0.abs_diff(b.0) + a.1.abs_diff(b.1)
+}
+
+fn neighbors(maze: &[Vec<char>], (i, j): Cell) -> impl Iterator<Item = Cell> + '_ {
+    let candidates = [
+        (i.wrapping_sub(1), j),
+        (i + 1, j),
+        (i, j.wrapping_sub(1)),
+        (i, j + 1),
+    ];
+    candidates.into_iter().filter(move |&c| is_walkable_cell(maze, c))
+}
+
+/// Camino más corto de `start` a `goal`, sin incluir `start`. Devuelve
+/// `None` si `goal` no se alcanza o alguna de las dos celdas es muro.
+pub fn find_path(maze: &[Vec<char>], start: Cell, goal: Cell) -> Option<Vec<Cell>> {
+    if !is_walkable_cell(maze, start) || !is_walkable_cell(maze, goal) {
+        return None;
+    }
+
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Cell, Cell> = HashMap::new();
+    let mut cost: HashMap<Cell, usize> = HashMap::new();
+
+    cost.insert(start, 0);
+    open.push(Reverse((manhattan(start, goal), start)));
+
+    while let Some(Reverse((_, current))) = open.pop() {
+        if current == goal {
+            let mut path = vec![current];
+            let mut cell = current;
+            while let Some(&prev) = came_from.get(&cell) {
+                if prev == start { break; }
+                path.push(prev);
+                cell = prev;
+            }
+            path.reverse();
+            if goal == start { path.clear(); }
+            return Some(path);
+        }
+
+        let g = cost[&current];
+        for next in neighbors(maze, current) {
+            let new_cost = g + 1;
+            if cost.get(&next).is_none_or(|&c| new_cost < c) {
+                cost.insert(next, new_cost);
+                came_from.insert(next, current);
+                open.push(Reverse((new_cost + manhattan(next, goal), next)));
+            }
+        }
+    }
+    None
+}
+
+/// Distancia BFS (en celdas) de cada celda caminable hasta `goal`, y el
+/// vecino por el que conviene seguir. Se reconstruye solo cuando cambia la
+/// celda objetivo.
+pub struct NavField {
+    width: usize,
+    height: usize,
+    goal: Option<Cell>,
+    dist: Vec<Option<u32>>,
+    next: Vec<Option<Cell>>,
+}
+
+impl NavField {
+    pub fn new() -> Self {
+        NavField { width: 0, height: 0, goal: None, dist: Vec::new(), next: Vec::new() }
+    }
+
+    pub fn goal(&self) -> Option<Cell> {
+        self.goal
+    }
+
+    /// Reconstruye el campo si `goal` cambió o el laberinto cambió de tamaño.
+    /// Devuelve `true` si hubo que reconstruirlo.
+    pub fn update(&mut self, maze: &[Vec<char>], goal: Cell) -> bool {
+        let width = maze.iter().map(|row| row.len()).max().unwrap_or(0);
+        if self.goal == Some(goal) && self.width == width && self.height == maze.len() {
+            return false;
+        }
+        self.build(maze, goal);
+        true
+    }
+
+    pub fn build(&mut self, maze: &[Vec<char>], goal: Cell) {
+        self.width = maze.iter().map(|row| row.len()).max().unwrap_or(0);
+        self.height = maze.len();
+        self.goal = Some(goal);
+        self.dist = vec![None; self.width * self.height];
+        self.next = vec![None; self.width * self.height];
+
+        if !is_walkable_cell(maze, goal) {
+            return;
+        }
+
+        let mut queue = VecDeque::new();
+        self.dist[goal.1 * self.width + goal.0] = Some(0);
+        queue.push_back(goal);
+
+        while let Some(cell) = queue.pop_front() {
+            let d = self.dist[cell.1 * self.width + cell.0].unwrap_or(0);
+            for n in neighbors(maze, cell) {
+                let idx = n.1 * self.width + n.0;
+                if self.dist[idx].is_none() {
+                    self.dist[idx] = Some(d + 1);
+                    // Desde `n` se llega al objetivo pasando por `cell`
+                    self.next[idx] = Some(cell);
+                    queue.push_back(n);
+                }
+            }
+        }
+    }
+
+    fn index(&self, (i, j): Cell) -> Option<usize> {
+        (i < self.width && j < self.height).then_some(j * self.width + i)
+    }
+
+    /// Pasos hasta el objetivo; `None` si la celda es muro o no lo alcanza.
+    pub fn distance(&self, cell: Cell) -> Option<u32> {
+        self.index(cell).and_then(|idx| self.dist[idx])
+    }
+
+    /// Vecino que acerca al objetivo.
+    pub fn next_toward(&self, cell: Cell) -> Option<Cell> {
+        self.index(cell).and_then(|idx| self.next[idx])
+    }
+
+    /// Vecino alcanzable más lejos del objetivo (para huir).
+    pub fn next_away(&self, maze: &[Vec<char>], cell: Cell) -> Option<Cell> {
+        let here = self.distance(cell)?;
+        neighbors(maze, cell)
+            .filter_map(|n| self.distance(n).map(|d| (d, n)))
+            .filter(|&(d, _)| d > here)
+            .max_by_key(|&(d, _)| d)
+            .map(|(_, n)| n)
+    }
+
+    /// Mayor distancia alcanzable (para escalar colores en el overlay).
+    pub fn max_distance(&self) -> u32 {
+        self.dist.iter().flatten().copied().max().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Vec<Vec<char>> {
+        rows.iter().map(|r| r.chars().collect()).collect()
+    }
+
+    #[test]
+    fn routes_around_walls() {
+        let maze = grid(&[
+            "#####",
+            "#   #",
+            "# # #",
+            "# #M#",
+            "#####",
+        ]);
+        let path = find_path(&maze, (1, 3), (3, 3)).unwrap();
+        assert_eq!(path, vec![(1, 2), (1, 1), (2, 1), (3, 1), (3, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn unreachable_goal_has_no_path() {
+        let maze = grid(&[
+            "#####",
+            "# # #",
+            "#####",
+        ]);
+        assert_eq!(find_path(&maze, (1, 1), (3, 1)), None);
+        assert_eq!(find_path(&maze, (1, 1), (1, 1)), Some(vec![]));
+    }
+
+    #[test]
+    fn field_points_every_cell_toward_the_goal() {
+        let maze = grid(&[
+            "#####",
+            "#   #",
+            "# # #",
+            "# # #",
+            "#####",
+        ]);
+        let mut field = NavField::new();
+        assert!(field.update(&maze, (1, 3)));
+        assert!(!field.update(&maze, (1, 3)));
+
+        assert_eq!(field.distance((3, 3)), Some(6));
+        assert_eq!(field.distance((2, 2)), None);
+        assert_eq!(field.next_toward((3, 1)), Some((2, 1)));
+        assert_eq!(field.next_toward((1, 3)), None);
+        assert_eq!(field.next_away(&maze, (2, 1)), Some((3, 1)));
+    }
+}
@@ -0,0 +1,167 @@
+// perception.rs
+// Lo que cada zombie sabe del jugador: lo ve, lo oye o lo recuerda.
+
+use raylib::prelude::*;
+use crate::caster::line_of_sight;
+use crate::maze::Maze;
+use crate::player::Player;
+
+// Distancia máxima de visión (unidades del mundo)
+const VIEW_DISTANCE: f32 = 640.0;
+// Medio ángulo del cono de visión
+const VIEW_HALF_ANGLE: f32 = std::f32::consts::PI / 3.0;
+// Segundos que recuerda la última posición conocida
+const MEMORY_TIME: f32 = 6.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Sense {
+    Sight,
+    Hearing,
+}
+
+pub struct Perception {
+    // Última posición conocida del jugador y cómo se supo
+    pub last_known: Option<Vector2>,
+    pub last_sense: Option<Sense>,
+    // Lo percibió en este frame
+    pub sees_player: bool,
+    pub hears_player: bool,
+    // Sentidos propios del arquetipo
+    pub view_distance: f32,
+    pub view_half_angle: f32,
+    // Multiplica el radio del ruido del jugador
+    pub hearing: f32,
+    memory: f32,
+}
+
+impl Perception {
+    pub fn new() -> Self {
+        Perception {
+            last_known: None,
+            last_sense: None,
+            sees_player: false,
+            hears_player: false,
+            view_distance: VIEW_DISTANCE,
+            view_half_angle: VIEW_HALF_ANGLE,
+            hearing: 1.0,
+            memory: 0.0,
+        }
+    }
+
+    pub fn with_senses(view_distance: f32, view_half_angle: f32, hearing: f32) -> Self {
+        Perception { view_distance, view_half_angle, hearing, ..Perception::new() }
+    }
+
+    pub fn can_see(&self, maze: &Maze, block_size: usize, pos: Vector2, facing: f32, target: Vector2) -> bool {
+        let (dx, dy) = (target.x - pos.x, target.y - pos.y);
+        if dx * dx + dy * dy > self.view_distance * self.view_distance {
+            return false;
+        }
+        let mut ang = dy.atan2(dx) - facing;
+        while ang >  std::f32::consts::PI { ang -= 2.0*std::f32::consts::PI; }
+        while ang < -std::f32::consts::PI { ang += 2.0*std::f32::consts::PI; }
+        ang.abs() <= self.view_half_angle && line_of_sight(maze, pos, target, block_size)
+    }
+
+    // El ruido atraviesa paredes; solo importa la distancia
+    pub fn can_hear(pos: Vector2, target: Vector2, noise: f32) -> bool {
+        let (dx, dy) = (target.x - pos.x, target.y - pos.y);
+        noise > 0.0 && dx * dx + dy * dy <= noise * noise
+    }
+
+    pub fn update(
+        &mut self,
+        maze: &Maze,
+        block_size: usize,
+        pos: Vector2,
+        facing: f32,
+        player: &Player,
+        dt: f32,
+    ) {
+        self.sees_player = self.can_see(maze, block_size, pos, facing, player.pos);
+        self.hears_player = Self::can_hear(pos, player.pos, player.noise * self.hearing);
+
+        if self.sees_player || self.hears_player {
+            self.last_known = Some(player.pos);
+            self.last_sense = Some(if self.sees_player { Sense::Sight } else { Sense::Hearing });
+            self.memory = MEMORY_TIME;
+        } else if self.memory > 0.0 {
+            self.memory -= dt;
+            if self.memory <= 0.0 {
+                self.forget();
+            }
+        }
+    }
+
+    // Un aviso de otro zombie cuenta como algo oído
+    pub fn remember(&mut self, pos: Vector2) {
+        self.last_known = Some(pos);
+        self.last_sense = Some(Sense::Hearing);
+        self.memory = MEMORY_TIME;
+    }
+
+    pub fn forget(&mut self) {
+        self.last_known = None;
+        self.last_sense = None;
+        self.memory = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BS: usize = 80;
+
+    fn maze() -> Maze {
+        [
+            "#######",
+            "#     #",
+            "#  #  #",
+            "#     #",
+            "#######",
+        ].iter().map(|r| r.chars().collect()).collect()
+    }
+
+    fn player_at(pos: Vector2, noise: f32) -> Player {
+        let mut player = Player::new(pos, 0.0, std::f32::consts::PI / 3.0);
+        player.noise = noise;
+        player
+    }
+
+    fn center(i: usize, j: usize) -> Vector2 {
+        Vector2::new((i as f32 + 0.5) * BS as f32, (j as f32 + 0.5) * BS as f32)
+    }
+
+    #[test]
+    fn sight_needs_cone_and_clear_line() {
+        let m = maze();
+        let p = Perception::new();
+        // Mirando al este con el jugador enfrente
+        assert!(p.can_see(&m, BS, center(1, 1), 0.0, center(5, 1)));
+        // De espaldas
+        assert!(!p.can_see(&m, BS, center(1, 1), std::f32::consts::PI, center(5, 1)));
+        // Con el muro del medio en el camino
+        assert!(!p.can_see(&m, BS, center(1, 2), 0.0, center(5, 2)));
+        // Un arquetipo miope no llega
+        let short = Perception::with_senses(200.0, VIEW_HALF_ANGLE, 1.0);
+        assert!(!short.can_see(&m, BS, center(1, 1), 0.0, center(5, 1)));
+    }
+
+    #[test]
+    fn remembers_last_known_position_for_a_while() {
+        let m = maze();
+        let mut p = Perception::new();
+        // Oye los pasos a través del muro
+        p.update(&m, BS, center(1, 2), std::f32::consts::PI, &player_at(center(5, 2), 400.0), 0.1);
+        assert!(p.hears_player && !p.sees_player);
+        assert_eq!(p.last_sense, Some(Sense::Hearing));
+
+        // El jugador se queda quieto y fuera de vista
+        p.update(&m, BS, center(1, 2), std::f32::consts::PI, &player_at(center(5, 3), 0.0), 1.0);
+        assert_eq!(p.last_known, Some(center(5, 2)));
+
+        p.update(&m, BS, center(1, 2), std::f32::consts::PI, &player_at(center(5, 3), 0.0), MEMORY_TIME);
+        assert_eq!(p.last_known, None);
+    }
+}
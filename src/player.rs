@@ -1,12 +1,24 @@
 // player.rs
 
 use raylib::prelude::*;
-use raylib::consts::{KeyboardKey, GamepadAxis, GamepadButton};
+use crate::input::{Action, Bindings};
+use crate::tiles::is_player_walkable;
+
+// Radio (unidades del mundo) en el que se oyen los pasos del jugador
+const WALK_NOISE: f32 = 160.0;
+const SPRINT_NOISE: f32 = 400.0;
+// Velocidad al caminar (unidades del mundo por segundo)
+pub const MOVE_SPEED: f32 = 230.0;
+// Radio del cuerpo: la cámara nunca se acerca a una pared más que esto
+const PLAYER_RADIUS: f32 = 16.0;
 
 pub struct Player {
     pub pos: Vector2,
     pub a: f32,
     pub fov: f32,
+    pub radius: f32,
+    // Radio del ruido que hizo en este frame (0 = quieto)
+    pub noise: f32,
     last_mouse_x: f32,
 }
 
@@ -16,6 +28,8 @@ impl Player {
             pos,
             a,
             fov,
+            radius: PLAYER_RADIUS,
+            noise: 0.0,
             last_mouse_x: 0.0,
         }
     }
@@ -24,124 +38,166 @@ impl Player {
 pub fn process_events(
     player: &mut Player,
     rl: &RaylibHandle,
+    bindings: &Bindings,
     maze: &crate::maze::Maze,
     block_size: usize,
 ) {
     let dt               = rl.get_frame_time().max(0.0001);
-    let base_move_speed  = 230.0;
-    let base_strafe_speed= 230.0;
-    let sprint_mult      = 1.6;           // L3 para sprint
-    let turn_speed_keys  = 2.9;           // rad/seg flechas
-    let mouse_sens       = 0.0030;        // rad por pixel
-    let pad_turn_sens    = 2.9;           // rad/seg por stick derecho
-    let dz               = 0.15;          // deadzone
+    let base_move_speed  = MOVE_SPEED;
+    let base_strafe_speed= MOVE_SPEED;
+    let sprint_mult      = 1.6;
+    let turn_speed_keys  = 2.9;           // rad/seg con teclas
+    let settings         = &bindings.settings;
 
     // Vectores base 
     let fwd   = Vector2 { x: player.a.cos(),  y: player.a.sin()  }; // adelante
-    let left  = Vector2 { x: player.a.sin(),  y: -player.a.cos() }; // izquierda 
     let right = Vector2 { x: -player.a.sin(), y: player.a.cos()  }; // derecha 
 
-    let mut wish = Vector2 { x: 0.0, y: 0.0 };
-
-    // W/S 
-    if rl.is_key_down(KeyboardKey::KEY_W) || rl.is_key_down(KeyboardKey::KEY_UP) {
-        wish.x += fwd.x;  wish.y += fwd.y;
-    }
-    if rl.is_key_down(KeyboardKey::KEY_S) || rl.is_key_down(KeyboardKey::KEY_DOWN) {
-        wish.x -= fwd.x;  wish.y -= fwd.y;
-    }
-
-    if rl.is_key_down(KeyboardKey::KEY_A) || rl.is_key_down(KeyboardKey::KEY_Q) {
-        wish.x += left.x; wish.y += left.y;  
-    }
-    if rl.is_key_down(KeyboardKey::KEY_D) || rl.is_key_down(KeyboardKey::KEY_E) {
-        wish.x += right.x; wish.y += right.y; 
-    }
+    // Teclas, botones y sticks llegan como acciones (ver input.rs)
+    let forward = bindings.value(rl, Action::MoveForward) - bindings.value(rl, Action::MoveBack);
+    let strafe  = bindings.value(rl, Action::StrafeRight) - bindings.value(rl, Action::StrafeLeft);
+    let wish = Vector2 {
+        x: fwd.x * forward + right.x * strafe,
+        y: fwd.y * forward + right.y * strafe,
+    };
 
-    // Rotación por teclas
-    let mut turn_axis = 0.0f32;
-    if rl.is_key_down(KeyboardKey::KEY_LEFT)  { turn_axis -= 1.0; }
-    if rl.is_key_down(KeyboardKey::KEY_RIGHT) { turn_axis += 1.0; }
-    player.a += turn_axis * turn_speed_keys * dt;
+    // Rotación: teclas a velocidad fija, stick según su sensibilidad
+    let turn_keys = bindings.digital(rl, Action::TurnRight) as i32 - bindings.digital(rl, Action::TurnLeft) as i32;
+    let turn_stick = bindings.analog(rl, Action::TurnRight) - bindings.analog(rl, Action::TurnLeft);
+    player.a += turn_keys as f32 * turn_speed_keys * dt;
+    player.a += turn_stick * settings.stick_sensitivity * dt;
 
     // Rotación por mouse 
     let mdx = rl.get_mouse_delta().x;
-    player.a += mdx * mouse_sens;
+    player.a += mdx * settings.mouse_sensitivity;
 
-    // Gamepad 
     let mut move_speed   = base_move_speed;
     let mut strafe_speed = base_strafe_speed;
+    let sprinting        = bindings.is_down(rl, Action::Sprint);
 
-    if rl.is_gamepad_available(0) {
-        if rl.is_gamepad_button_down(0, GamepadButton::GAMEPAD_BUTTON_LEFT_THUMB) {
-            move_speed   *= sprint_mult;
-            strafe_speed *= sprint_mult;
-        }
-
-        let mut lx = rl.get_gamepad_axis_movement(0, GamepadAxis::GAMEPAD_AXIS_LEFT_X);
-        let mut ly = rl.get_gamepad_axis_movement(0, GamepadAxis::GAMEPAD_AXIS_LEFT_Y);
-        if lx.abs() < dz { lx = 0.0; }
-        if ly.abs() < dz { ly = 0.0; }
-
-        wish.x += (-ly) * fwd.x;   wish.y += (-ly) * fwd.y;   
-        wish.x += ( lx) * right.x; wish.y += ( lx) * right.y; 
-
-        let mut rx = rl.get_gamepad_axis_movement(0, GamepadAxis::GAMEPAD_AXIS_RIGHT_X);
-        if rx.abs() < dz { rx = 0.0; }
-        player.a += rx * pad_turn_sens * dt;
-
-        if rl.is_gamepad_button_down(0, GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_1)
-            || rl.is_gamepad_button_down(0, GamepadButton::GAMEPAD_BUTTON_LEFT_TRIGGER_2)
-        {
-            wish.x += left.x; wish.y += left.y; 
-        }
-        if rl.is_gamepad_button_down(0, GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_1)
-            || rl.is_gamepad_button_down(0, GamepadButton::GAMEPAD_BUTTON_RIGHT_TRIGGER_2)
-        {
-            wish.x += right.x; wish.y += right.y; 
-        }
+    if sprinting {
+        move_speed   *= sprint_mult;
+        strafe_speed *= sprint_mult;
     }
 
     while player.a >  std::f32::consts::PI { player.a -= 2.0*std::f32::consts::PI; }
     while player.a <= -std::f32::consts::PI { player.a += 2.0*std::f32::consts::PI; }
 
     let mag = (wish.x*wish.x + wish.y*wish.y).sqrt();
+    player.noise = if mag <= 0.0001 { 0.0 } else if sprinting { SPRINT_NOISE } else { WALK_NOISE };
     if mag > 0.0001 {
         let dir = Vector2 { x: wish.x / mag, y: wish.y / mag };
 
         let vx = dir.x * move_speed * dt;
         let vy = dir.y * strafe_speed * dt;
+        player.pos = move_and_slide(maze, block_size, player.pos, Vector2::new(vx, vy), player.radius);
+    }
+}
 
-        let can_pass = |nx: f32, ny: f32| -> bool {
-            let i = (nx / block_size as f32) as usize;
-            let j = (ny / block_size as f32) as usize;
-            if j >= maze.len() || i >= maze[0].len() { return false; }
-            let cell = maze[j][i];
-            cell == ' ' || cell == 'M' || cell == 'g'
-        };
-
-        // Eje X
-        let nx = player.pos.x + vx;
-        if can_pass(nx, player.pos.y) { player.pos.x = nx; }
-        // Eje Y
-        let ny = player.pos.y + vy;
-        if can_pass(player.pos.x, ny) { player.pos.y = ny; }
+fn is_solid_cell(maze: &crate::maze::Maze, i: isize, j: isize) -> bool {
+    if i < 0 || j < 0 { return true; }
+    match maze.get(j as usize).and_then(|row| row.get(i as usize)) {
+        Some(&cell) => !is_player_walkable(cell),
+        None => true,
     }
 }
 
+// Empuje que saca al círculo de la celda (i, j), o `None` si no la toca
+fn push_out_of_cell(pos: Vector2, radius: f32, i: isize, j: isize, bs: f32) -> Option<Vector2> {
+    let (left, top) = (i as f32 * bs, j as f32 * bs);
+    let closest = Vector2::new(pos.x.clamp(left, left + bs), pos.y.clamp(top, top + bs));
+    let d = pos - closest;
+    let dist2 = d.x * d.x + d.y * d.y;
+    if dist2 >= radius * radius { return None; }
+
+    if dist2 > 1e-8 {
+        // Borde o esquina convexa: sale en la dirección del punto más cercano
+        let dist = dist2.sqrt();
+        Some(d * ((radius - dist) / dist))
+    } else {
+        // Centro dentro de la celda: sale por el lado más cercano
+        let exits = [
+            (pos.x - left + radius, Vector2::new(-1.0, 0.0)),
+            (left + bs - pos.x + radius, Vector2::new(1.0, 0.0)),
+            (pos.y - top + radius, Vector2::new(0.0, -1.0)),
+            (top + bs - pos.y + radius, Vector2::new(0.0, 1.0)),
+        ];
+        let (depth, normal) = exits.into_iter().min_by(|a, b| a.0.total_cmp(&b.0))?;
+        Some(normal * depth)
+    }
+}
 
-fn can_move_to(pos: Vector2, maze: &crate::maze::Maze, block_size: usize) -> bool {
-    let i = (pos.x / block_size as f32) as usize;
-    let j = (pos.y / block_size as f32) as usize;
-    
-    if maze.is_empty() || maze[0].is_empty() {
-        return false;
+/// Mueve un círculo `delta` y lo separa de las celdas sólidas que toque, de
+/// modo que se desliza por las paredes y rodea las esquinas. Avanza en pasos
+/// menores que el radio para no atravesar paredes finas.
+pub fn move_and_slide(maze: &crate::maze::Maze, block_size: usize, pos: Vector2, delta: Vector2, radius: f32) -> Vector2 {
+    let bs = block_size as f32;
+    let len = (delta.x * delta.x + delta.y * delta.y).sqrt();
+    let steps = (len / (radius * 0.5).max(1.0)).ceil().max(1.0) as usize;
+    let step = delta * (1.0 / steps as f32);
+
+    let mut p = pos;
+    for _ in 0..steps {
+        p += step;
+        // Unas pocas pasadas bastan para resolver esquinas interiores
+        for _ in 0..3 {
+            let (i0, i1) = (((p.x - radius) / bs).floor() as isize, ((p.x + radius) / bs).floor() as isize);
+            let (j0, j1) = (((p.y - radius) / bs).floor() as isize, ((p.y + radius) / bs).floor() as isize);
+            let mut pushed = false;
+            for j in j0..=j1 {
+                for i in i0..=i1 {
+                    if !is_solid_cell(maze, i, j) { continue; }
+                    if let Some(push) = push_out_of_cell(p, radius, i, j, bs) {
+                        p += push;
+                        pushed = true;
+                    }
+                }
+            }
+            if !pushed { break; }
+        }
     }
-    
-    if j >= maze.len() || i >= maze[0].len() {
-        return false;
+    p
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BS: usize = 80;
+
+    fn grid(rows: &[&str]) -> crate::maze::Maze {
+        rows.iter().map(|r| r.chars().collect()).collect()
     }
-    
-    let cell = maze[j][i];
-    cell == ' ' || cell == 'M' || cell == 'g'
-}
\ No newline at end of file
+
+    // Sin tocar paredes, un movimiento nulo no lo empuja
+    fn clear_of_walls(maze: &crate::maze::Maze, p: Vector2) -> bool {
+        move_and_slide(maze, BS, p, Vector2::zero(), 16.0) == p
+    }
+
+    #[test]
+    fn stops_a_radius_away_from_walls() {
+        let maze = grid(&["#####", "#   #", "#####"]);
+        let p = move_and_slide(&maze, BS, Vector2::new(200.0, 120.0), Vector2::new(0.0, -200.0), 16.0);
+        assert!((p.y - (80.0 + 16.0)).abs() < 0.01);
+        assert!(clear_of_walls(&maze, p));
+        assert!(push_out_of_cell(Vector2::new(200.0, 90.0), 16.0, 2, 0, BS as f32).is_some());
+        assert!(!clear_of_walls(&maze, Vector2::new(200.0, 90.0)));
+    }
+
+    #[test]
+    fn slides_along_walls_and_around_corners() {
+        let maze = grid(&["#####", "#   #", "# # #", "#   #", "#####"]);
+        // Empujando en diagonal contra la pared de arriba avanza en X
+        let p = move_and_slide(&maze, BS, Vector2::new(120.0, 100.0), Vector2::new(60.0, -60.0), 16.0);
+        assert!(p.x > 175.0 && (p.y - 96.0).abs() < 0.01);
+
+        // Rozando la esquina convexa del pilar central no se traba
+        let mut p = Vector2::new(100.0, 140.0);
+        for _ in 0..40 {
+            p = move_and_slide(&maze, BS, p, Vector2::new(0.0, 5.0), 16.0);
+            p = move_and_slide(&maze, BS, p, Vector2::new(3.0, 0.0), 16.0);
+        }
+        assert!(p.y > 220.0);
+        assert!(clear_of_walls(&maze, p));
+    }
+}
@@ -0,0 +1,223 @@
+// renderer.rs
+// Vista 3D por software: escribe todo en un Framebuffer, sin ventana.
+
+use raylib::prelude::*;
+use crate::caster::{cast_ray, WallFace};
+use crate::framebuffer::Framebuffer;
+use crate::maze::Maze;
+use crate::player::Player;
+use crate::texture::{TextureData, TextureManager};
+use crate::sprites::{view_index, Sprite};
+use crate::tiles::tile;
+
+const DISTANCE_TO_PROJECTION_PLANE: f32 = 100.0;
+
+pub struct Scene<'a> {
+    pub maze: &'a Maze,
+    // Grilla opcional con la clave de textura de suelo de cada celda
+    pub floor_map: Option<&'a Maze>,
+    pub block_size: usize,
+    pub player: &'a Player,
+    pub sprites: &'a [Sprite],
+    pub textures: &'a TextureManager,
+}
+
+fn shade(color: Color, factor: f32) -> Color {
+    Color::new(
+        (color.r as f32 * factor) as u8,
+        (color.g as f32 * factor) as u8,
+        (color.b as f32 * factor) as u8,
+        color.a,
+    )
+}
+
+/// Dibuja techo, suelo, muros y sprites. Al terminar, `zbuffer` guarda la
+/// distancia perpendicular al muro de cada columna.
+pub fn render_world(framebuffer: &mut Framebuffer, zbuffer: &mut Vec<f32>, scene: &Scene) {
+    framebuffer.set_background_color(Color::new(30, 30, 40, 255));
+    framebuffer.clear();
+
+    zbuffer.clear();
+    zbuffer.resize(framebuffer.width as usize, f32::INFINITY);
+
+    render_floor_and_ceiling(framebuffer, scene);
+    render_walls(framebuffer, zbuffer, scene);
+    render_sprites(framebuffer, zbuffer, scene);
+}
+
+fn render_floor_and_ceiling(framebuffer: &mut Framebuffer, scene: &Scene) {
+    let (Some(ceiling_tex), Some(floor_tex)) =
+        (scene.textures.get_ceiling_texture(), scene.textures.get_floor_texture())
+    else {
+        return;
+    };
+
+    let player = scene.player;
+    let bs = scene.block_size as f32;
+    let num_rays = framebuffer.width as usize;
+    let hh = framebuffer.height as f32 / 2.0;
+
+    // Misma dirección por columna que los muros, escalada para avanzar una
+    // unidad de distancia perpendicular (sin efecto ojo de pez)
+    let rays: Vec<(f32, f32)> = (0..num_rays)
+        .map(|i| {
+            let a = player.a - (player.fov / 2.0) + (player.fov * i as f32 / num_rays as f32);
+            let k = 1.0 / (a - player.a).cos();
+            (a.cos() * k, a.sin() * k)
+        })
+        .collect();
+
+    for y in (hh.ceil() as u32)..framebuffer.height {
+        // La cámara está a media altura del muro: la fila a `p` píxeles del
+        // horizonte ve el suelo (y el techo, simétrico) a esta distancia
+        let p = y as f32 + 0.5 - hh;
+        let row_distance = DISTANCE_TO_PROJECTION_PLANE * hh / (2.0 * p);
+        let fog = (200.0 / row_distance).clamp(0.3, 1.0);
+        let ceiling_y = framebuffer.height - 1 - y;
+
+        for (x, &(ray_x, ray_y)) in rays.iter().enumerate() {
+            // Coordenadas del mundo en unidades de celda; cada celda es una repetición de la textura
+            let cx = (player.pos.x + ray_x * row_distance) / bs;
+            let cy = (player.pos.y + ray_y * row_distance) / bs;
+
+            let tile = scene.floor_map
+                .and_then(|map| map.get(cy.floor() as usize)?.get(cx.floor() as usize).copied())
+                .filter(|_| cx >= 0.0 && cy >= 0.0);
+            let ftex = tile
+                .and_then(|t| scene.textures.get_floor_texture_for(t))
+                .unwrap_or(floor_tex);
+
+            framebuffer.set_pixel_color(x as u32, y, shade(ftex.sample(cx, cy), fog));
+            framebuffer.set_pixel_color(x as u32, ceiling_y, shade(ceiling_tex.sample(cx, cy), fog));
+        }
+    }
+}
+
+fn render_walls(framebuffer: &mut Framebuffer, zbuffer: &mut [f32], scene: &Scene) {
+    let player = scene.player;
+    let num_rays = framebuffer.width as usize;
+    let screen_h = framebuffer.height as f32;
+    let hh = screen_h / 2.0;
+
+    for (i, depth) in zbuffer.iter_mut().enumerate().take(num_rays) {
+        let current_ray = i as f32 / num_rays as f32;
+        let a = player.a - (player.fov / 2.0) + (player.fov * current_ray);
+
+        let intersect = cast_ray(framebuffer, scene.maze, player, a, scene.block_size, false);
+
+        let distance_to_wall = (intersect.distance * (a - player.a).cos()).max(0.0001);
+        let stake_height = (hh / distance_to_wall) * DISTANCE_TO_PROJECTION_PLANE;
+        let stake_top = hh - stake_height / 2.0;
+
+        let y0 = stake_top.max(0.0) as u32;
+        let y1 = (stake_top + stake_height).min(screen_h).max(0.0) as u32;
+
+        // Las caras N/S un poco más oscuras para distinguir esquinas
+        let side_shade = match intersect.face {
+            WallFace::North | WallFace::South => 0.8,
+            WallFace::East | WallFace::West => 1.0,
+        };
+        let fog_factor = (200.0 / distance_to_wall).clamp(0.3, 1.0) * side_shade;
+
+        let wtex = tile(intersect.impact).texture.and_then(|key| scene.textures.get_wall_texture(key));
+        let wall_color = tile(intersect.impact).wall_color;
+
+        for y in y0..y1 {
+            let color = match wtex {
+                Some(tex) => {
+                    let v = (y as f32 + 0.5 - stake_top) / stake_height;
+                    tex.sample(intersect.tex_u, v)
+                }
+                None => wall_color,
+            };
+            framebuffer.set_pixel_color(i as u32, y, shade(color, fog_factor));
+        }
+
+        *depth = distance_to_wall;
+    }
+}
+
+/// Dibuja cualquier lista de sprites como billboards, de atrás hacia
+/// adelante y ocultos por los muros según el zbuffer.
+fn render_sprites(framebuffer: &mut Framebuffer, zbuffer: &[f32], scene: &Scene) {
+    let player_pos = scene.player.pos;
+    let dist2 = |s: &Sprite| {
+        let (dx, dy) = (s.pos.x - player_pos.x, s.pos.y - player_pos.y);
+        dx * dx + dy * dy
+    };
+
+    let mut order: Vec<&Sprite> = scene.sprites.iter().collect();
+    order.sort_by(|a, b| dist2(b).partial_cmp(&dist2(a)).unwrap_or(std::cmp::Ordering::Equal));
+
+    for sprite in order {
+        // Sprites direccionales: la vista depende de hacia dónde mira y desde dónde se lo ve
+        let key = sprite.image.texture_key();
+        let view = sprite.facing.and_then(|facing| {
+            scene.textures.get_view(key, view_index(sprite.pos, facing, player_pos))
+        });
+        let (tex, mirror) = match view {
+            Some((tex, mirror)) => (Some(tex), mirror),
+            None => (scene.textures.get_texture(key), false),
+        };
+        if let Some(tex) = tex {
+            draw_billboard(framebuffer, zbuffer, scene.player, sprite, tex, mirror);
+        }
+    }
+}
+
+fn draw_billboard(
+    framebuffer: &mut Framebuffer,
+    zbuffer: &[f32],
+    player: &Player,
+    sprite: &Sprite,
+    tex: &TextureData,
+    mirror: bool,
+) {
+    let screen_w = framebuffer.width as f32;
+    let screen_h = framebuffer.height as f32;
+    let hh = screen_h / 2.0;
+
+    let dx = sprite.pos.x - player.pos.x;
+    let dy = sprite.pos.y - player.pos.y;
+
+    let mut ang = dy.atan2(dx) - player.a;
+    while ang >  std::f32::consts::PI { ang -= 2.0*std::f32::consts::PI; }
+    while ang < -std::f32::consts::PI { ang += 2.0*std::f32::consts::PI; }
+
+    if ang.abs() > player.fov * 0.75 { return; }
+
+    let perp = (dx*dx + dy*dy).sqrt() * ang.cos();
+    if perp <= 0.01 { return; }
+
+    // Alto de un muro a esta distancia; el suelo queda en hh + wall_h / 2
+    let wall_h = (hh / perp) * DISTANCE_TO_PROJECTION_PLANE;
+    let sprite_h = wall_h * sprite.scale;
+    if !sprite_h.is_finite() || sprite_h <= 1.0 { return; }
+    let rect = sprite.image.rect();
+    let aspect = (tex.width as f32 * rect.w) / (tex.height as f32 * rect.h);
+    let sprite_w = sprite_h * aspect * sprite.width_factor;
+
+    let center_x = ((ang / player.fov) + 0.5) * screen_w;
+    let left = center_x - sprite_w / 2.0;
+    let top = hh + wall_h / 2.0 - wall_h * sprite.v_offset - sprite_h;
+
+    let sx0 = left.floor().max(0.0) as usize;
+    let sx1 = ((left + sprite_w).ceil().min(screen_w)).max(0.0) as usize;
+    let sy0 = top.max(0.0) as u32;
+    let sy1 = (top + sprite_h).min(screen_h).max(0.0) as u32;
+
+    let fog = (220.0 / perp).clamp(0.35, 1.0);
+
+    for (sx, &depth) in zbuffer.iter().enumerate().take(sx1).skip(sx0) {
+        if perp >= depth { continue; }
+
+        let u = ((sx as f32 + 0.5 - left) / sprite_w).clamp(0.0, 0.9999);
+        let u = if mirror { 0.9999 - u } else { u };
+        for sy in sy0..sy1 {
+            let v = ((sy as f32 + 0.5 - top) / sprite_h).clamp(0.0, 0.9999);
+            let texel = tex.sample(rect.u + u * rect.w, rect.v + v * rect.h);
+            if texel.a < 128 { continue; }
+            framebuffer.set_pixel_color(sx as u32, sy, shade(texel, fog));
+        }
+    }
+}
@@ -1,60 +1,291 @@
 // Sprites.rs
+// Todo lo que se dibuja como billboard: zombies, suministros, salida, props.
 use raylib::prelude::*;
-use crate::framebuffer::Framebuffer;
-use std::time::{Duration, Instant};
+use std::collections::HashMap;
+use crate::maze::Maze;
+use crate::tiles::tile;
 
+// Rectángulo de un cuadro dentro de la textura, normalizado a [0, 1]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrameRect {
+    pub u: f32,
+    pub v: f32,
+    pub w: f32,
+    pub h: f32,
+}
+
+impl FrameRect {
+    pub const FULL: FrameRect = FrameRect { u: 0.0, v: 0.0, w: 1.0, h: 1.0 };
+}
+
+// Textura con varios cuadros (clave del TextureManager + rectángulos)
+#[derive(Clone)]
+pub struct SpriteSheet {
+    pub texture: char,
+    pub frames: Vec<FrameRect>,
+}
+
+impl SpriteSheet {
+    /// Grilla regular de `cols` x `rows` cuadros, numerados fila por fila.
+    pub fn grid(texture: char, cols: usize, rows: usize) -> Self {
+        let (w, h) = (1.0 / cols as f32, 1.0 / rows as f32);
+        let frames = (0..rows)
+            .flat_map(|r| (0..cols).map(move |c| FrameRect { u: c as f32 * w, v: r as f32 * h, w, h }))
+            .collect();
+        SpriteSheet { texture, frames }
+    }
+
+    /// Atlas con rectángulos arbitrarios `(x, y, ancho, alto)` en píxeles.
+    pub fn atlas(texture: char, sheet_w: usize, sheet_h: usize, rects: &[(usize, usize, usize, usize)]) -> Self {
+        let (sw, sh) = (sheet_w as f32, sheet_h as f32);
+        let frames = rects.iter()
+            .map(|&(x, y, w, h)| FrameRect { u: x as f32 / sw, v: y as f32 / sh, w: w as f32 / sw, h: h as f32 / sh })
+            .collect();
+        SpriteSheet { texture, frames }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlayMode {
+    Loop,
+    // Se queda en el último cuadro
+    Once,
+}
+
+#[derive(Clone)]
+pub struct Clip {
+    // Índices dentro de SpriteSheet::frames
+    pub frames: Vec<usize>,
+    pub frame_duration: f32,
+    pub mode: PlayMode,
+}
+
+impl Clip {
+    pub fn new(frames: Vec<usize>, frame_duration: f32, mode: PlayMode) -> Self {
+        Clip { frames, frame_duration, mode }
+    }
+}
+
+// Reproduce clips con nombre ("walk", "attack", ...) de una SpriteSheet
+#[derive(Clone)]
 pub struct AnimatedSprite {
-    pub pos: Vector2,
-    pub frames: Vec<Color>, 
+    pub sheet: SpriteSheet,
+    clips: HashMap<String, Clip>,
+    current_clip: String,
     pub current_frame: usize,
-    pub frame_duration: Duration,
-    pub last_frame_time: Instant,
-    pub size: u32,
+    elapsed: f32,
+    // Multiplicador de velocidad de reproducción (1.0 = normal)
+    pub speed: f32,
 }
 
 impl AnimatedSprite {
-    pub fn new(pos: Vector2, size: u32) -> Self {
-        let frames = vec![
-            Color::new(100, 200, 100, 255), 
-            Color::new(150, 255, 150, 255), 
-            Color::new(200, 255, 200, 255), 
-            Color::new(150, 255, 150, 255), 
-        ];
-
+    pub fn new(sheet: SpriteSheet) -> Self {
         AnimatedSprite {
-            pos,
-            frames,
+            sheet,
+            clips: HashMap::new(),
+            current_clip: String::new(),
             current_frame: 0,
-            frame_duration: Duration::from_millis(200), 
-            last_frame_time: Instant::now(),
-            size,
+            elapsed: 0.0,
+            speed: 1.0,
+        }
+    }
+
+    pub fn with_clip(mut self, name: &str, clip: Clip) -> Self {
+        if self.current_clip.is_empty() {
+            self.current_clip = name.to_string();
         }
+        self.clips.insert(name.to_string(), clip);
+        self
     }
 
-    pub fn update(&mut self) {
-        let now = Instant::now();
-        if now.duration_since(self.last_frame_time) >= self.frame_duration {
-            self.current_frame = (self.current_frame + 1) % self.frames.len();
-            self.last_frame_time = now;
+    pub fn clip_name(&self) -> &str {
+        &self.current_clip
+    }
+
+    // Cambia de clip; si ya se está reproduciendo no lo reinicia
+    pub fn play(&mut self, name: &str) {
+        if self.current_clip != name && self.clips.contains_key(name) {
+            self.current_clip = name.to_string();
+            self.restart();
         }
     }
 
-    pub fn render(&self, framebuffer: &mut Framebuffer, _block_size: usize) {
-        let color = self.frames[self.current_frame];
-        framebuffer.set_current_color(color);
+    pub fn restart(&mut self) {
+        self.current_frame = 0;
+        self.elapsed = 0.0;
+    }
 
-        let x = (self.pos.x) as usize;
-        let y = (self.pos.y) as usize;
+    // Un clip Once llegó a su último cuadro
+    pub fn is_finished(&self) -> bool {
+        match self.clips.get(&self.current_clip) {
+            Some(clip) => clip.mode == PlayMode::Once && self.current_frame + 1 >= clip.frames.len(),
+            None => true,
+        }
+    }
 
-        for dx in 0..self.size {
-            for dy in 0..self.size {
-                let px = x + dx as usize;
-                let py = y + dy as usize;
-                
-                if px < framebuffer.width as usize && py < framebuffer.height as usize {
-                    framebuffer.set_pixel(px as u32, py as u32);
-                }
+    pub fn update(&mut self, dt: f32) {
+        let Some(clip) = self.clips.get(&self.current_clip) else { return; };
+        if clip.frames.is_empty() || clip.frame_duration <= 0.0 {
+            return;
+        }
+        self.elapsed += dt * self.speed;
+        while self.elapsed >= clip.frame_duration {
+            self.elapsed -= clip.frame_duration;
+            match clip.mode {
+                PlayMode::Loop => self.current_frame = (self.current_frame + 1) % clip.frames.len(),
+                PlayMode::Once => self.current_frame = (self.current_frame + 1).min(clip.frames.len() - 1),
             }
         }
     }
-}
\ No newline at end of file
+
+    pub fn image(&self) -> SpriteImage {
+        let rect = self.clips.get(&self.current_clip)
+            .and_then(|clip| clip.frames.get(self.current_frame))
+            .and_then(|&i| self.sheet.frames.get(i))
+            .copied()
+            .unwrap_or(FrameRect::FULL);
+        SpriteImage::Frame(self.sheet.texture, rect)
+    }
+}
+
+pub enum SpriteImage {
+    Texture(char),
+    // Un cuadro de una sprite sheet
+    Frame(char, FrameRect),
+}
+
+impl SpriteImage {
+    pub fn texture_key(&self) -> char {
+        match self {
+            SpriteImage::Texture(key) | SpriteImage::Frame(key, _) => *key,
+        }
+    }
+
+    pub fn rect(&self) -> FrameRect {
+        match self {
+            SpriteImage::Texture(_) => FrameRect::FULL,
+            SpriteImage::Frame(_, rect) => *rect,
+        }
+    }
+}
+
+// Vistas de un sprite direccional: (hoja de vista a usar, espejar en horizontal).
+// La vista 0 es el frente y cada siguiente gira 45° en el sentido de los
+// ángulos (1 frente-derecha, 2 derecha, ..., 4 espalda, ..., 6 izquierda).
+pub type ViewSet = [(usize, bool); 8];
+
+/// Vista (0..8) con la que `viewer` ve un sprite en `pos` orientado hacia `facing`.
+pub fn view_index(pos: Vector2, facing: f32, viewer: Vector2) -> usize {
+    let to_viewer = (viewer.y - pos.y).atan2(viewer.x - pos.x);
+    let rel = (to_viewer - facing).rem_euclid(std::f32::consts::TAU);
+    (rel / (std::f32::consts::TAU / 8.0)).round() as usize % 8
+}
+
+pub struct Sprite {
+    pub pos: Vector2,
+    pub image: SpriteImage,
+    // Alto como fracción del alto de un muro
+    pub scale: f32,
+    // Elevación sobre el suelo, también en fracción del alto de un muro
+    pub v_offset: f32,
+    // Ancho relativo (< 1 simula que el sprite está girando)
+    pub width_factor: f32,
+    // Hacia dónde mira (radianes); solo importa si la textura tiene vistas
+    pub facing: Option<f32>,
+}
+
+impl Sprite {
+    pub fn new(pos: Vector2, image: SpriteImage) -> Self {
+        Sprite {
+            pos,
+            image,
+            scale: 1.0,
+            v_offset: 0.0,
+            width_factor: 1.0,
+            facing: None,
+        }
+    }
+}
+
+// Celdas del laberinto que se dibujan como sprite en vez de muro
+pub fn is_sprite_cell(c: char) -> bool {
+    tile(c).billboard
+}
+
+/// Sprites fijos del laberinto: una por celda con `billboard` en tiles.rs
+/// (suministros, salida, camillas...), centrado en su celda. Con `time_s`
+/// los que tienen `animated` flotan y giran.
+pub fn maze_sprites(maze: &Maze, block_size: usize, time_s: Option<f32>) -> Vec<Sprite> {
+    let bs = block_size as f32;
+    let mut sprites = Vec::new();
+
+    for (j, row) in maze.iter().enumerate() {
+        for (i, &cell) in row.iter().enumerate() {
+            if !is_sprite_cell(cell) { continue; }
+
+            let pos = Vector2::new((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs);
+            let def = tile(cell);
+            let mut sprite = Sprite {
+                scale: def.sprite_scale,
+                v_offset: def.sprite_offset,
+                ..Sprite::new(pos, SpriteImage::Texture(def.texture.unwrap_or(cell)))
+            };
+            if let (true, Some(t)) = (def.animated, time_s) {
+                sprite.v_offset += 0.05 * (t * 3.0 + (i + j) as f32).sin();
+                sprite.width_factor = (t * 2.0 + i as f32).cos().abs().max(0.15);
+            }
+            sprites.push(sprite);
+        }
+    }
+    sprites
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn walker() -> AnimatedSprite {
+        AnimatedSprite::new(SpriteSheet::grid('z', 4, 2))
+            .with_clip("walk", Clip::new(vec![0, 1, 2, 3], 0.1, PlayMode::Loop))
+            .with_clip("attack", Clip::new(vec![4, 5], 0.2, PlayMode::Once))
+    }
+
+    #[test]
+    fn grid_frames_are_numbered_row_by_row() {
+        let sheet = SpriteSheet::grid('z', 4, 2);
+        assert_eq!(sheet.frames.len(), 8);
+        assert_eq!(sheet.frames[5], FrameRect { u: 0.25, v: 0.5, w: 0.25, h: 0.5 });
+    }
+
+    #[test]
+    fn loop_wraps_and_once_holds_last_frame() {
+        let mut anim = walker();
+        anim.update(0.45);
+        assert_eq!(anim.current_frame, 0);
+
+        anim.play("attack");
+        anim.update(1.0);
+        assert_eq!(anim.current_frame, 1);
+        assert!(anim.is_finished());
+        assert_eq!(anim.image().rect(), SpriteSheet::grid('z', 4, 2).frames[5]);
+    }
+
+    #[test]
+    fn view_follows_facing_relative_to_viewer() {
+        let pos = Vector2::new(100.0, 100.0);
+        let viewer = Vector2::new(200.0, 100.0);
+        assert_eq!(view_index(pos, 0.0, viewer), 0);
+        assert_eq!(view_index(pos, std::f32::consts::PI, viewer), 4);
+        // Mirando al sur (y crece hacia abajo) el jugador al este le ve el lado izquierdo
+        assert_eq!(view_index(pos, std::f32::consts::FRAC_PI_2, viewer), 6);
+        assert_eq!(view_index(pos, -std::f32::consts::FRAC_PI_4, viewer), 1);
+    }
+
+    #[test]
+    fn speed_scales_playback() {
+        let mut anim = walker();
+        anim.speed = 2.0;
+        anim.update(0.175);
+        assert_eq!(anim.current_frame, 3);
+    }
+}
@@ -1,162 +1,210 @@
-// texture.rs 
+// texture.rs
 use raylib::prelude::*;
 use std::collections::HashMap;
+use crate::sprites::ViewSet;
+
+// Caracteres de floor.txt y la textura de suelo que eligen. Es una tabla
+// aparte: un 'M' o una 'g' en el mapa de suelos no pinta sprites en el piso
+const FLOOR_KEYS: &[(char, char)] = &[(' ', 'F'), ('.', 'F'), ('O', 'O')];
+
+// Texels en memoria (CPU) para el renderer por software
+pub struct TextureData {
+    pub width: usize,
+    pub height: usize,
+    pixels: Vec<Color>,
+}
+
+impl TextureData {
+    pub fn new(width: usize, height: usize, fill: Color) -> Self {
+        TextureData { width, height, pixels: vec![fill; width * height] }
+    }
+
+    pub fn from_image(image: &Image) -> Self {
+        let width = image.width().max(0) as usize;
+        let height = image.height().max(0) as usize;
+        TextureData { width, height, pixels: image.get_image_data().to_vec() }
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, color: Color) {
+        if x < self.width && y < self.height {
+            self.pixels[y * self.width + x] = color;
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Color {
+        self.pixels[y.min(self.height - 1) * self.width + x.min(self.width - 1)]
+    }
+
+    /// Muestreo nearest con coordenadas normalizadas; u y v se repiten fuera de [0, 1).
+    pub fn sample(&self, u: f32, v: f32) -> Color {
+        let x = (u.rem_euclid(1.0) * self.width as f32) as usize;
+        let y = (v.rem_euclid(1.0) * self.height as f32) as usize;
+        self.get(x, y)
+    }
+}
+
+// Hoja de una vista: clave del sprite y número de vista (0 = frente). Va en
+// un mapa aparte para no ocupar caracteres del mapa ni de la leyenda
+type ViewKey = (char, usize);
 
 pub struct TextureManager {
-    textures: HashMap<char, Texture2D>,
+    textures: HashMap<char, TextureData>,
+    // Vistas por dirección, indexadas por la clave de la vista frontal
+    views: HashMap<char, ViewSet>,
+    // Hojas de las vistas 1..8; la vista 0 es la textura normal
+    view_textures: HashMap<ViewKey, TextureData>,
     texture_size: usize,
 }
 
+// Vistas opcionales del zombie (sprite sheets con la misma grilla que 'z')
+const ZOMBIE_VIEWS: [&str; 8] = [
+    "front", "front_right", "right", "back_right", "back", "back_left", "left", "front_left",
+];
+const ZOMBIE_SHEET_GRID: (usize, usize) = (4, 2);
+
 impl TextureManager {
-    pub fn new(rl: &mut RaylibHandle, thread: &RaylibThread) -> Self {
+    pub fn new() -> Self {
         let mut manager = TextureManager {
             textures: HashMap::new(),
+            views: HashMap::new(),
+            view_textures: HashMap::new(),
             texture_size: 64,
         };
-        manager.load_textures(rl, thread);
+        manager.load_textures(true);
         manager
     }
 
-    fn try_load_texture_any(
-        rl: &mut RaylibHandle,
-        thread: &RaylibThread,
-        paths: &[&str],
-        label: &str,
-    ) -> Option<Texture2D> {
-        for p in paths {
-            if let Ok(t) = rl.load_texture(thread, p) {
-                println!("[textures] Loaded {label} via load_texture: {p}");
-                return Some(t);
-            }
-        }
+    // Solo texturas procedurales: no depende de archivos en disco
+    pub fn procedural() -> Self {
+        let mut manager = TextureManager {
+            textures: HashMap::new(),
+            views: HashMap::new(),
+            view_textures: HashMap::new(),
+            texture_size: 64,
+        };
+        manager.load_textures(false);
+        manager
+    }
+
+    fn try_load(paths: &[&str], label: &str) -> Option<TextureData> {
         for p in paths {
             if let Ok(img) = Image::load_image(p) {
-                println!("[textures] Loaded {label} as Image: {p}, creating Texture2D");
-                if let Ok(t) = rl.load_texture_from_image(thread, &img) {
-                    return Some(t);
-                }
+                println!("[textures] Loaded {label} from: {p}");
+                return Some(TextureData::from_image(&img));
             }
         }
-        println!("[textures] Could not load {label} from {:?}", paths);
+        println!("[textures] Image not found for {label}, using procedural fallback");
         None
     }
-    
-    fn load_textures(&mut self, rl: &mut RaylibHandle, thread: &RaylibThread) {
-        fn try_load(rl: &mut RaylibHandle, thread: &RaylibThread, paths: &[&str], label: &str)
-            -> Option<Texture2D>
-        {
-            for p in paths {
-                match rl.load_texture(thread, p) {
-                    Ok(t) => { println!("[textures] Loaded {label} from: {p}"); return Some(t); }
-                    Err(_) => { /* sigue probando */ }
-                }
-            }
-            println!("[textures] PNG not found for {label}, using procedural fallback");
-            None
-        }
 
-        //  WALLS ('#', '+', '-', '|') 
-        if let Some(tex) = try_load(rl, thread, &[
-            "textures/wall_horizontal.png",
-            "wall_horizontal.png",
-        ], "wall") {
-            self.textures.insert('#', tex);
-            if let Some(t) = try_load(rl, thread, &["textures/wall_horizontal.png","wall_horizontal.png"], "wall") { self.textures.insert('+', t); }
-            if let Some(t) = try_load(rl, thread, &["textures/wall_horizontal.png","wall_horizontal.png"], "wall") { self.textures.insert('-', t); }
-            if let Some(t) = try_load(rl, thread, &["textures/wall_horizontal.png","wall_horizontal.png"], "wall") { self.textures.insert('|', t); }
-        } else {
-            let img = Self::create_wall_horizontal_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) { self.textures.insert('#', t); }
-            let img2 = Self::create_wall_horizontal_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img2) { self.textures.insert('+', t); }
-            let img3 = Self::create_wall_horizontal_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img3) { self.textures.insert('-', t); }
-            let img4 = Self::create_wall_horizontal_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img4) { self.textures.insert('|', t); }
-        }
-
-        //  MEDICAL ('M') 
-        if let Some(tex) = try_load(rl, thread, &[
-            "textures/medical_supply.png",
-            "medical_supply.png",
-        ], "medical") {
-            self.textures.insert('M', tex);
-        } else {
-            let img = Self::create_medical_supply_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) { self.textures.insert('M', t); }
-        }
+    fn load_textures(&mut self, from_files: bool) {
+        let size = self.texture_size;
+        let mut load = |key: char, paths: &[&str], label: &str, fallback: fn(usize) -> TextureData| {
+            let tex = if from_files { Self::try_load(paths, label) } else { None };
+            self.textures.insert(key, tex.unwrap_or_else(|| fallback(size)));
+        };
+
+        //  WALLS ('#'; '+', '-', '|' usan la misma)
+        load('#', &["textures/wall_horizontal.png", "wall_horizontal.png"], "wall", Self::create_wall_horizontal_image);
+
+        //  MEDICAL ('M')
+        load('M', &["textures/medical_supply.png", "medical_supply.png"], "medical", Self::create_medical_supply_image);
+
+        // ZOMBIE ('Z')
+        load('Z', &["textures/zombie.png", "zombie.png"], "zombie", Self::create_zombie_image);
+
+        //  EXIT ('g')
+        load('g', &["textures/exit_door.png", "exit_door.png"], "exit", Self::create_exit_door_image);
+
+        //  PROPS: camilla ('b') y portasueros ('i')
+        load('b', &["textures/gurney.png", "gurney.png"], "gurney", Self::create_gurney_image);
+        load('i', &["textures/iv_stand.png", "iv_stand.png"], "iv stand", Self::create_iv_stand_image);
+
+        //  FLOOR ('F')
+        load('F', &["textures/floor.png", "floor.png"], "floor", Self::create_floor_image);
+
+        //  FLOOR DEL QUIRÓFANO ('O', solo en el mapa de suelos)
+        load('O', &["textures/floor_operating.png", "floor_operating.png"], "operating floor", Self::create_operating_floor_image);
 
-        // ZOMBIE ('Z') 
-        if let Some(tex) = Self::try_load_texture_any(
-            rl, thread,
-            &["textures/zombie.png", "zombie.png"],
-            "zombie",
-        ) {
-            self.textures.insert('Z', tex);
+        //  CEILING ('C')
+        load('C', &["textures/ceiling.png", "ceiling.png"], "ceiling", Self::create_ceiling_image);
+
+        //  ZOMBIE SHEET ('z'): grilla 4x2, fila 0 caminar y fila 1 atacar.
+        // Sin archivo se arma a partir de la textura 'Z'
+        let sheet = if from_files {
+            Self::try_load(&["textures/zombie_sheet.png", "zombie_sheet.png"], "zombie sheet")
         } else {
-            let mut img = Image::gen_image_color(self.texture_size as i32, self.texture_size as i32, Color::new(20,20,20,0));
-            for y in 8..(self.texture_size-8) {
-                for x in 8..(self.texture_size-8) {
-                    img.draw_pixel(x as i32, y as i32, Color::new(120, 200, 120, 255));
-                }
-            }
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) {
-                self.textures.insert('Z', t);
+            None
+        };
+        let sheet = sheet.unwrap_or_else(|| Self::create_zombie_sheet(&self.textures[&'Z']));
+        self.textures.insert('z', sheet);
+
+        self.load_zombie_views(from_files);
+    }
+
+    // Sin archivos de perfil ni de espalda se generan a partir de la hoja
+    // frontal. Cada vista que falta usa la opuesta espejada y, si tampoco
+    // está, el perfil de su lado (derecha o izquierda espejada)
+    fn load_zombie_views(&mut self, from_files: bool) {
+        let mut found = [false; 8];
+        found[0] = true;
+        for (i, name) in ZOMBIE_VIEWS.iter().enumerate().skip(1) {
+            if !from_files { break; }
+            let file = format!("textures/zombie_{name}.png");
+            if let Some(tex) = Self::try_load(&[&file], &format!("zombie {name}")) {
+                self.view_textures.insert(('z', i), tex);
+                found[i] = true;
             }
         }
 
-        //  EXIT ('g') 
-        if let Some(tex) = try_load(rl, thread, &[
-            "textures/exit_door.png",
-            "exit_door.png",
-        ], "exit") {
-            self.textures.insert('g', tex);
-        } else {
-            let img = Self::create_exit_door_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) { self.textures.insert('g', t); }
+        let front = &self.textures[&'z'];
+        if !found[2] && !found[6] {
+            let side = Self::create_side_view(front, ZOMBIE_SHEET_GRID);
+            self.view_textures.insert(('z', 2), side);
+            found[2] = true;
         }
-
-        //  FLOOR ('F') 
-        if let Some(tex) = try_load(rl, thread, &[
-            "textures/floor.png",
-            "floor.png",
-        ], "floor") {
-            self.textures.insert('F', tex);
-        } else {
-            let img = Self::create_floor_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) { self.textures.insert('F', t); }
+        if !found[4] {
+            let back = Self::create_back_view(front, ZOMBIE_SHEET_GRID);
+            self.view_textures.insert(('z', 4), back);
+            found[4] = true;
         }
 
-        //  CEILING ('C') 
-        if let Some(tex) = Self::try_load_texture_any(
-            rl, thread,
-            &["textures/ceiling.png", "ceiling.png"],
-            "ceiling",
-        ) {
-            self.textures.insert('C', tex);
-        } else {
-            let img = Self::create_ceiling_image(self.texture_size);
-            if let Ok(t) = rl.load_texture_from_image(thread, &img) { self.textures.insert('C', t); }
+        let resolve = |i: usize| {
+            let mirror_of = (8 - i) % 8;
+            if found[i] {
+                Some((i, false))
+            } else if found[mirror_of] {
+                Some((mirror_of, true))
+            } else {
+                None
+            }
+        };
+        let mut views: ViewSet = [(0, false); 8];
+        for (i, view) in views.iter_mut().enumerate() {
+            let side = if i < 4 { 2 } else { 6 };
+            // El perfil siempre existe: cargado o generado arriba
+            *view = resolve(i).or_else(|| resolve(side)).unwrap_or((0, false));
         }
+        self.views.insert('z', views);
     }
 
-    //  Procedurales (fallback) 
-    fn create_wall_horizontal_image(texture_size: usize) -> Image {
-        let mut image = Image::gen_image_color(texture_size as i32, texture_size as i32, Color::LIGHTGRAY);
+    //  Procedurales (fallback)
+    fn create_wall_horizontal_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::LIGHTGRAY);
         for y in 0..texture_size {
             for x in 0..texture_size {
                 let mut color = Color::new(200, 195, 190, 255);
                 if y % 8 == 0 { color = Color::new(180, 175, 170, 255); }
-                let brick_offset = if (y / 8) % 2 == 0 { 0 } else { 16 };
+                let brick_offset = if (y / 8).is_multiple_of(2) { 0 } else { 16 };
                 if (x + brick_offset) % 32 == 0 { color = Color::new(180, 175, 170, 255); }
-                image.draw_pixel(x as i32, y as i32, color);
+                image.set(x, y, color);
             }
         }
         image
     }
 
-    fn create_medical_supply_image(texture_size: usize) -> Image {
-        let mut image = Image::gen_image_color(texture_size as i32, texture_size as i32, Color::WHITE);
+    fn create_medical_supply_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::WHITE);
         for y in 0..texture_size {
             for x in 0..texture_size {
                 let mut color = Color::new(250, 250, 255, 255);
@@ -167,14 +215,95 @@ impl TextureManager {
                 let cy = texture_size / 2;
                 if y > cy - 6 && y < cy + 6 && x > 10 && x < texture_size - 10 { color = Color::new(220, 20, 20, 255); }
                 if x > cx - 6 && x < cx + 6 && y > 10 && y < texture_size - 10 { color = Color::new(220, 20, 20, 255); }
-                image.draw_pixel(x as i32, y as i32, color);
+                image.set(x, y, color);
             }
         }
         image
     }
 
-    fn create_exit_door_image(texture_size: usize) -> Image {
-        let mut image = Image::gen_image_color(texture_size as i32, texture_size as i32, Color::GREEN);
+    fn create_zombie_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::new(20, 20, 20, 0));
+        for y in 8..(texture_size - 8) {
+            for x in 8..(texture_size - 8) {
+                image.set(x, y, Color::new(120, 200, 120, 255));
+            }
+        }
+        image
+    }
+
+    // Cuadros derivados del sprite base: balanceo al caminar y embestida roja al atacar
+    fn create_zombie_sheet(base: &TextureData) -> TextureData {
+        let (w, h) = (base.width, base.height);
+        let step = (w / 32).max(1) as isize;
+        // (desplazamiento x, desplazamiento y, tinte rojo) por cuadro
+        let frames: [(isize, isize, f32); 8] = [
+            (0, 0, 0.0), (step, -step, 0.0), (0, 0, 0.0), (-step, -step, 0.0),
+            (0, step, 0.2), (0, -2 * step, 0.45), (0, step, 0.2), (0, -2 * step, 0.45),
+        ];
+
+        let mut sheet = TextureData::new(w * 4, h * 2, Color::new(0, 0, 0, 0));
+        for (k, &(dx, dy, tint)) in frames.iter().enumerate() {
+            let (ox, oy) = ((k % 4) * w, (k / 4) * h);
+            for y in 0..h {
+                for x in 0..w {
+                    let (sx, sy) = (x as isize - dx, y as isize - dy);
+                    if sx < 0 || sy < 0 || sx >= w as isize || sy >= h as isize { continue; }
+                    let c = base.get(sx as usize, sy as usize);
+                    let r = c.r as f32 + (255.0 - c.r as f32) * tint;
+                    let g = c.g as f32 * (1.0 - tint);
+                    let b = c.b as f32 * (1.0 - tint);
+                    sheet.set(ox + x, oy + y, Color::new(r as u8, g as u8, b as u8, c.a));
+                }
+            }
+        }
+        sheet
+    }
+
+    // Perfil: cada cuadro angostado hacia su centro
+    fn create_side_view(sheet: &TextureData, (cols, rows): (usize, usize)) -> TextureData {
+        let (w, h) = (sheet.width / cols, sheet.height / rows);
+        let mut side = TextureData::new(sheet.width, sheet.height, Color::new(0, 0, 0, 0));
+        for frame in 0..cols * rows {
+            let (ox, oy) = ((frame % cols) * w, (frame / cols) * h);
+            for y in 0..h {
+                for x in 0..w {
+                    let sx = w as f32 / 2.0 + (x as f32 + 0.5 - w as f32 / 2.0) / 0.6;
+                    if sx < 0.0 || sx >= w as f32 { continue; }
+                    side.set(ox + x, oy + y, sheet.get(ox + sx as usize, oy + y));
+                }
+            }
+        }
+        side
+    }
+
+    // Espalda: misma silueta, pero cada fila con su color medio oscurecido
+    // para que no se vea la cara
+    fn create_back_view(sheet: &TextureData, (cols, rows): (usize, usize)) -> TextureData {
+        let (w, h) = (sheet.width / cols, sheet.height / rows);
+        let mut back = TextureData::new(sheet.width, sheet.height, Color::new(0, 0, 0, 0));
+        for frame in 0..cols * rows {
+            let (ox, oy) = ((frame % cols) * w, (frame / cols) * h);
+            for y in 0..h {
+                let row: Vec<Color> = (0..w).map(|x| sheet.get(ox + x, oy + y)).collect();
+                let solid: Vec<&Color> = row.iter().filter(|c| c.a > 0).collect();
+                if solid.is_empty() { continue; }
+                let avg = |f: fn(&Color) -> u8| {
+                    let sum: u32 = solid.iter().map(|c| f(c) as u32).sum();
+                    (sum / solid.len() as u32) as f32 * 0.7
+                };
+                let (r, g, b) = (avg(|c| c.r), avg(|c| c.g), avg(|c| c.b));
+                for (x, c) in row.iter().enumerate() {
+                    if c.a > 0 {
+                        back.set(ox + x, oy + y, Color::new(r as u8, g as u8, b as u8, c.a));
+                    }
+                }
+            }
+        }
+        back
+    }
+
+    fn create_exit_door_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::GREEN);
         for y in 0..texture_size {
             for x in 0..texture_size {
                 let mut color = Color::new(20, 120, 40, 255);
@@ -183,60 +312,163 @@ impl TextureManager {
                 }
                 if x > 16 && x < 48 && y > 18 && y < 46 {
                     color = Color::new(0, 180, 0, 255);
-                    if y > 22 && y < 42 {
-                        if (x > 20 && x < 24) || (x > 26 && x < 30) ||
-                           (x > 32 && x < 36) || (x > 38 && x < 44) {
-                            color = Color::WHITE;
-                        }
+                    if y > 22 && y < 42 &&
+                       ((x > 20 && x < 24) || (x > 26 && x < 30) ||
+                        (x > 32 && x < 36) || (x > 38 && x < 44)) {
+                        color = Color::WHITE;
                     }
                 }
-                image.draw_pixel(x as i32, y as i32, color);
+                image.set(x, y, color);
+            }
+        }
+        image
+    }
+
+    fn create_gurney_image(texture_size: usize) -> TextureData {
+        let (w, h) = (texture_size, texture_size / 2);
+        let mut image = TextureData::new(w, h, Color::new(0, 0, 0, 0));
+        for y in 0..h {
+            for x in 0..w {
+                // Colchón con sábana, marco metálico y cuatro patas
+                let color = if y < h / 3 && x >= 2 && x < w - 2 {
+                    Some(Color::new(235, 240, 245, 255))
+                } else if (h / 3..h / 3 + 3).contains(&y) {
+                    Some(Color::new(140, 145, 150, 255))
+                } else if y >= h / 3 + 3 && (x % (w / 3) < 2 || x >= w - 2) {
+                    Some(Color::new(110, 110, 115, 255))
+                } else {
+                    None
+                };
+                if let Some(c) = color { image.set(x, y, c); }
+            }
+        }
+        image
+    }
+
+    fn create_iv_stand_image(texture_size: usize) -> TextureData {
+        let (w, h) = (texture_size / 2, texture_size);
+        let mut image = TextureData::new(w, h, Color::new(0, 0, 0, 0));
+        let cx = w / 2;
+        for y in 0..h {
+            for x in 0..w {
+                let pole = x >= cx - 1 && x <= cx && y >= 4;
+                let bag = (6..20).contains(&y) && (cx + 2..cx + 9).contains(&x);
+                let base = y >= h - 3 && x >= 4 && x < w - 4;
+                let color = if bag {
+                    Some(Color::new(200, 230, 255, 220))
+                } else if pole || base {
+                    Some(Color::new(150, 150, 160, 255))
+                } else {
+                    None
+                };
+                if let Some(c) = color { image.set(x, y, c); }
             }
         }
         image
     }
 
-    fn create_floor_image(texture_size: usize) -> Image {
-        let mut image = Image::gen_image_color(texture_size as i32, texture_size as i32, Color::LIGHTGRAY);
+    fn create_floor_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::LIGHTGRAY);
         for y in 0..texture_size {
             for x in 0..texture_size {
                 let tile = 16;
                 let tile_x = x / tile;
                 let tile_y = y / tile;
-                let mut color = if (tile_x + tile_y) % 2 == 0 {
+                let mut color = if (tile_x + tile_y).is_multiple_of(2) {
                     Color::new(180, 180, 185, 255)
                 } else {
                     Color::new(170, 170, 175, 255)
                 };
                 if x % tile < 2 || y % tile < 2 { color = Color::new(150, 150, 155, 255); }
-                image.draw_pixel(x as i32, y as i32, color);
+                image.set(x, y, color);
+            }
+        }
+        image
+    }
+
+    fn create_operating_floor_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::new(150, 190, 180, 255));
+        for y in 0..texture_size {
+            for x in 0..texture_size {
+                let tile = 8;
+                let mut color = Color::new(150, 190, 180, 255);
+                if x % tile == 0 || y % tile == 0 { color = Color::new(120, 160, 150, 255); }
+                image.set(x, y, color);
             }
         }
         image
     }
 
-    fn create_ceiling_image(texture_size: usize) -> Image {
-        let mut image = Image::gen_image_color(texture_size as i32, texture_size as i32, Color::new(230, 230, 240, 255));
+    fn create_ceiling_image(texture_size: usize) -> TextureData {
+        let mut image = TextureData::new(texture_size, texture_size, Color::new(230, 230, 240, 255));
         for y in 0..texture_size {
             for x in 0..texture_size {
                 let panel = 32;
                 let mut color = Color::new(230, 230, 240, 255);
                 if x % panel < 2 || y % panel < 2 { color = Color::new(200, 200, 210, 255); }
-                image.draw_pixel(x as i32, y as i32, color);
+                image.set(x, y, color);
             }
         }
         image
     }
 
-    //  Getters 
-    pub fn get_wall_texture(&self, wall_type: char) -> Option<&Texture2D> {
+    //  Getters
+    pub fn get_texture(&self, key: char) -> Option<&TextureData> { self.textures.get(&key) }
+    // Hoja y espejado de la vista `index` (ver sprites::view_index); None si
+    // la textura no tiene vistas
+    pub fn get_view(&self, key: char, index: usize) -> Option<(&TextureData, bool)> {
+        let (view, mirror) = self.views.get(&key)?[index];
+        let tex = if view == 0 { self.textures.get(&key) } else { self.view_textures.get(&(key, view)) };
+        tex.map(|tex| (tex, mirror))
+    }
+    pub fn get_wall_texture(&self, wall_type: char) -> Option<&TextureData> {
         self.textures.get(&wall_type).or_else(|| self.textures.get(&'#'))
     }
-    pub fn get_floor_texture(&self) -> Option<&Texture2D> { self.textures.get(&'F') }
-    pub fn get_ceiling_texture(&self) -> Option<&Texture2D> { self.textures.get(&'C') }
-    pub fn get_exit_texture(&self) -> Option<&Texture2D> { self.textures.get(&'g') }
-    pub fn get_medical_texture(&self) -> Option<&Texture2D> { self.textures.get(&'M') }
+    pub fn get_floor_texture(&self) -> Option<&TextureData> { self.textures.get(&'F') }
+    // Suelo por celda según FLOOR_KEYS; lo desconocido es suelo normal
+    pub fn get_floor_texture_for(&self, tile: char) -> Option<&TextureData> {
+        let key = FLOOR_KEYS.iter().find(|&&(c, _)| c == tile).map_or('F', |&(_, key)| key);
+        self.textures.get(&key)
+    }
+    pub fn get_ceiling_texture(&self) -> Option<&TextureData> { self.textures.get(&'C') }
+    pub fn get_exit_texture(&self) -> Option<&TextureData> { self.textures.get(&'g') }
+    pub fn get_medical_texture(&self) -> Option<&TextureData> { self.textures.get(&'M') }
     pub fn get_texture_size(&self) -> usize { self.texture_size }
-    pub fn get_zombie_texture(&self) -> Option<&Texture2D> { self.textures.get(&'Z') }
+    pub fn get_zombie_texture(&self) -> Option<&TextureData> { self.textures.get(&'Z') }
+
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
+    #[test]
+    fn floor_map_only_uses_floor_textures() {
+        let textures = TextureManager::procedural();
+        let floor = textures.get_floor_texture().unwrap();
+        for c in [' ', '.', 'M', 'g', 'Z', 'C', '3', 'x'] {
+            assert!(std::ptr::eq(textures.get_floor_texture_for(c).unwrap(), floor), "'{}'", c);
+        }
+        assert!(std::ptr::eq(textures.get_floor_texture_for('O').unwrap(), textures.get_texture('O').unwrap()));
+    }
+
+    #[test]
+    fn procedural_zombie_views_never_show_the_front_from_behind() {
+        let textures = TextureManager::procedural();
+        let front = textures.get_texture('z').unwrap();
+        let view = |i| textures.get_view('z', i).unwrap();
+
+        assert!(std::ptr::eq(view(0).0, front) && !view(0).1);
+        for i in 1..8 {
+            assert!(!std::ptr::eq(view(i).0, front), "view {i} reuses the front sheet");
+        }
+        // Izquierda = derecha espejada, y la espalda no se espeja
+        assert!(std::ptr::eq(view(6).0, view(2).0) && view(6).1 && !view(2).1);
+        assert!(!view(4).1);
+        assert!(textures.get_view('M', 0).is_none());
+        // Las vistas no ocupan claves de texturas
+        for c in '1'..='7' {
+            assert!(textures.get_texture(c).is_none());
+        }
+    }
 }
@@ -0,0 +1,186 @@
+// tiles.rs
+// Tabla única con lo que significa cada carácter de maze.txt. El resto del
+// juego pregunta aquí en vez de comparar caracteres sueltos.
+
+use raylib::prelude::Color;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pickup {
+    MedicalSupply,
+    Exit,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct TileDef {
+    pub ch: char,
+    pub name: &'static str,
+    // El raycaster lo dibuja como muro
+    pub solid: bool,
+    pub player_walkable: bool,
+    pub zombie_walkable: bool,
+    pub blocks_sight: bool,
+    // Clave en TextureManager (None = color plano)
+    pub texture: Option<char>,
+    // Color del muro cuando falta la textura
+    pub wall_color: Color,
+    // Se dibuja como sprite centrado en la celda
+    pub billboard: bool,
+    // Alto y elevación del sprite, en fracción del alto de un muro
+    pub sprite_scale: f32,
+    pub sprite_offset: f32,
+    // El sprite flota y gira con el tiempo de juego
+    pub animated: bool,
+    // Colores en la vista 2D, en el minimapa de la pantalla y en el
+    // minimapa del framebuffer
+    pub map_color: Color,
+    pub minimap_color: Color,
+    pub framebuffer_minimap_color: Color,
+    pub pickup: Option<Pickup>,
+}
+
+const NONE: Color = Color::new(0, 0, 0, 0);
+
+const FLOOR: TileDef = TileDef {
+    ch: ' ',
+    name: "floor",
+    solid: false,
+    player_walkable: true,
+    zombie_walkable: true,
+    blocks_sight: false,
+    texture: Some('F'),
+    wall_color: NONE,
+    billboard: false,
+    sprite_scale: 1.0,
+    sprite_offset: 0.0,
+    animated: false,
+    map_color: NONE,
+    minimap_color: NONE,
+    framebuffer_minimap_color: NONE,
+    pickup: None,
+};
+
+const WALL: TileDef = TileDef {
+    ch: '#',
+    name: "wall",
+    solid: true,
+    player_walkable: false,
+    zombie_walkable: false,
+    blocks_sight: true,
+    texture: Some('#'),
+    wall_color: Color::LIGHTGRAY,
+    map_color: Color::new(100, 100, 100, 255),
+    minimap_color: Color::WHITE,
+    framebuffer_minimap_color: Color::new(140, 140, 140, 255),
+    ..FLOOR
+};
+
+// Objeto del laberinto dibujado como sprite; por defecto bloquea el paso
+const PROP: TileDef = TileDef {
+    player_walkable: false,
+    zombie_walkable: false,
+    billboard: true,
+    map_color: Color::new(100, 100, 100, 255),
+    minimap_color: Color::WHITE,
+    framebuffer_minimap_color: Color::new(140, 140, 140, 255),
+    ..FLOOR
+};
+
+const TILES: &[TileDef] = &[
+    FLOOR,
+    TileDef {
+        ch: '+',
+        name: "wall corner",
+        map_color: Color::new(120, 120, 180, 255),
+        framebuffer_minimap_color: Color::new(200, 200, 200, 255),
+        ..WALL
+    },
+    TileDef {
+        ch: '-',
+        map_color: Color::new(140, 100, 100, 255),
+        framebuffer_minimap_color: Color::new(160, 160, 160, 255),
+        ..WALL
+    },
+    TileDef {
+        ch: '|',
+        map_color: Color::new(100, 140, 100, 255),
+        framebuffer_minimap_color: Color::new(160, 160, 160, 255),
+        ..WALL
+    },
+    TileDef {
+        ch: '#',
+        name: "solid wall",
+        map_color: Color::new(200, 60, 60, 255),
+        framebuffer_minimap_color: Color::new(220, 100, 100, 255),
+        ..WALL
+    },
+    TileDef {
+        ch: 'M',
+        name: "medical supply",
+        player_walkable: true,
+        zombie_walkable: true,
+        texture: Some('M'),
+        sprite_scale: 0.4,
+        sprite_offset: 0.15,
+        animated: true,
+        map_color: Color::new(0, 150, 255, 255),
+        minimap_color: Color::CYAN,
+        framebuffer_minimap_color: Color::new(0, 200, 255, 255),
+        pickup: Some(Pickup::MedicalSupply),
+        ..PROP
+    },
+    TileDef {
+        ch: 'g',
+        name: "exit",
+        player_walkable: true,
+        zombie_walkable: true,
+        texture: Some('g'),
+        sprite_scale: 0.9,
+        map_color: Color::new(0, 255, 0, 255),
+        minimap_color: Color::GREEN,
+        framebuffer_minimap_color: Color::new(0, 255, 0, 255),
+        pickup: Some(Pickup::Exit),
+        ..PROP
+    },
+    TileDef { ch: 'b', name: "gurney", texture: Some('b'), sprite_scale: 0.45, ..PROP },
+    TileDef { ch: 'i', name: "IV stand", texture: Some('i'), sprite_scale: 0.85, ..PROP },
+];
+
+// Cualquier otro carácter se comporta como muro
+const UNKNOWN: TileDef = TileDef { ch: '?', name: "unknown", wall_color: Color::GRAY, ..WALL };
+
+pub fn tile(c: char) -> &'static TileDef {
+    TILES.iter().find(|t| t.ch == c).unwrap_or(&UNKNOWN)
+}
+
+pub fn tiles() -> &'static [TileDef] {
+    TILES
+}
+
+pub fn is_player_walkable(c: char) -> bool {
+    tile(c).player_walkable
+}
+
+pub fn is_zombie_walkable(c: char) -> bool {
+    tile(c).zombie_walkable
+}
+
+pub fn blocks_sight(c: char) -> bool {
+    tile(c).blocks_sight
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_chars_are_walls_and_entries_are_unique() {
+        assert!(tile('X').solid && !is_player_walkable('X') && blocks_sight('X'));
+        assert_eq!(tile('M').pickup, Some(Pickup::MedicalSupply));
+        assert!(!is_zombie_walkable('b') && !blocks_sight('b'));
+        for (n, t) in tiles().iter().enumerate() {
+            assert!(tiles()[n + 1..].iter().all(|o| o.ch != t.ch), "duplicate tile '{}'", t.ch);
+            // Un muro que se puede cruzar sería invisible por dentro
+            assert!(!(t.solid && (t.player_walkable || t.zombie_walkable)));
+        }
+    }
+}
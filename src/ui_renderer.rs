@@ -5,6 +5,8 @@ use crate::framebuffer::Framebuffer;
 use crate::maze::Maze;
 use crate::player::Player;
 use crate::game_state::{GameState, GameData};
+use crate::tiles::tile;
+use crate::input::{Action, Bindings, RebindMenu};
 use std::time::Duration;
 
 fn draw_centered_text(
@@ -78,7 +80,7 @@ pub fn render_minimap(
     let center_y = minimap_radius + 25;
     
     let maze_height = maze.len();
-    let maze_width = if maze_height > 0 { maze[0].len() } else { 0 };
+    let maze_width = maze.iter().map(|row| row.len()).max().unwrap_or(0);
     
     let scale_x = (minimap_radius as f32 * 1.2) / maze_width as f32;
     let scale_y = (minimap_radius as f32 * 1.2) / maze_height as f32;
@@ -95,17 +97,17 @@ pub fn render_minimap(
         for y in (center_y - minimap_radius)..(center_y + minimap_radius) {
             let dx = x - center_x;
             let dy = y - center_y;
-            if dx * dx + dy * dy <= minimap_radius * minimap_radius {
-                if x >= 0 && y >= 0 && x < framebuffer.width as i32 && y < framebuffer.height as i32 {
-                    framebuffer.set_pixel(x as u32, y as u32);
-                }
+            if dx * dx + dy * dy <= minimap_radius * minimap_radius
+                && x >= 0 && y >= 0 && x < framebuffer.width as i32 && y < framebuffer.height as i32
+            {
+                framebuffer.set_pixel(x as u32, y as u32);
             }
         }
     }
 
     framebuffer.set_current_color(Color::new(100, 150, 255, 255));
     for angle in 0..360 {
-        let rad = angle as f32 * 3.14159 / 180.0;
+        let rad = (angle as f32).to_radians();
         let border_x = center_x + (minimap_radius as f32 * rad.cos()) as i32;
         let border_y = center_y + (minimap_radius as f32 * rad.sin()) as i32;
         
@@ -118,15 +120,7 @@ pub fn render_minimap(
     for (row_index, row) in maze.iter().enumerate() {
         for (col_index, &cell) in row.iter().enumerate() {
             if cell != ' ' {
-                let color = match cell {
-                    'g' => Color::new(0, 255, 0, 255),     // Salida 
-                    'M' => Color::new(0, 200, 255, 255),   // Suministros 
-                    '+' => Color::new(200, 200, 200, 255), // Esquinas
-                    '-' | '|' => Color::new(160, 160, 160, 255), // Paredes
-                    '#' => Color::new(220, 100, 100, 255), // Paredes sólidas
-                    _ => Color::new(140, 140, 140, 255),
-                };
-                framebuffer.set_current_color(color);
+                framebuffer.set_current_color(tile(cell).framebuffer_minimap_color);
 
                 let offset_x = center_x - (minimap_radius * 2 / 3);
                 let offset_y = center_y - (minimap_radius * 2 / 3);
@@ -194,8 +188,9 @@ pub fn render_minimap(
 
 pub fn render_main_menu_into(
     d: &mut RaylibDrawHandle,
+    bindings: &Bindings,
     window_width: i32,
-    window_height: i32,
+    _window_height: i32,
     blink_color: Color,
 ) {
     d.clear_background(Color::new(20, 20, 30, 255));
@@ -231,8 +226,10 @@ pub fn render_main_menu_into(
     );
 
     draw_centered_text(d, "Press ENTER", 510, 24, blink_color, window_width);
+    let controls = format!("{} = Controls", bindings.hint(Action::Controls));
+    draw_centered_text(d, &controls, 540, 18, Color::LIGHTGRAY, window_width);
 
-    draw_centered_text(d, "Good luck, survivor...", 560, 18, Color::DARKGRAY, window_width);
+    draw_centered_text(d, "Good luck, survivor...", 575, 18, Color::DARKGRAY, window_width);
 }
 
 pub fn render_victory_screen_into(
@@ -264,3 +261,45 @@ pub fn render_victory_screen_into(
     y += 70;
     draw_centered_text(d, hint, y, 20, Color::new(255, 220, 100, 255), screen_width);
 }
+
+pub fn render_pause_screen_into(d: &mut RaylibDrawHandle, bindings: &Bindings, screen_width: i32, screen_height: i32) {
+    d.clear_background(Color::new(10, 10, 15, 255));
+
+    let y = (screen_height as f32 * 0.40) as i32;
+    draw_centered_text(d, "PAUSED", y, 50, Color::new(255, 220, 100, 255), screen_width);
+    let hint = format!(
+        "{} or {} = Resume | {} = Controls",
+        bindings.hint(Action::Pause), bindings.hint(Action::Interact), bindings.hint(Action::Controls)
+    );
+    draw_centered_text(d, &hint, y + 70, 22, Color::LIGHTGRAY, screen_width);
+}
+
+pub fn render_controls_screen_into(
+    d: &mut RaylibDrawHandle,
+    bindings: &Bindings,
+    menu: &RebindMenu,
+    screen_width: i32,
+) {
+    d.clear_background(Color::new(20, 20, 30, 255));
+
+    draw_centered_text(d, "CONTROLS", 60, 40, Color::new(220, 60, 60, 255), screen_width);
+    draw_centered_text(
+        d,
+        "Up/Down = Select | ENTER = Rebind | DEL = Clear | Left/Right = Adjust | BACKSPACE = Save & back",
+        110, 16, Color::LIGHTGRAY, screen_width,
+    );
+
+    let x_label = screen_width / 2 - 420;
+    let x_value = screen_width / 2 - 150;
+    for row in 0..RebindMenu::rows() {
+        let (label, value) = menu.row_text(bindings, row);
+        let y = 160 + row as i32 * 34;
+        let selected = row == menu.selected;
+        if selected {
+            d.draw_rectangle(x_label - 10, y - 6, 860, 30, Color::new(60, 60, 90, 220));
+        }
+        let color = if selected && menu.listening { Color::YELLOW } else if selected { Color::WHITE } else { Color::GRAY };
+        d.draw_text(&label, x_label, y, 20, color);
+        d.draw_text(&value, x_value, y, 20, color);
+    }
+}
@@ -1,22 +1,320 @@
 // zombie.rs
 use raylib::prelude::*;
+use rand::Rng;
+use std::collections::HashMap;
+use crate::archetypes::Archetype;
+use crate::maze::Maze;
+use crate::nav::{cell_at, find_path, is_walkable_cell, Cell, NavField};
+use crate::perception::Perception;
+use crate::player::Player;
+use crate::sprites::{AnimatedSprite, Clip, PlayMode, Sprite, SpriteSheet};
+
+// A esta distancia del jugador (viéndolo) empieza a preparar el golpe
+const ATTACK_RANGE: f32 = 60.0;
+// Al terminar la preparación, el golpe conecta si el jugador sigue así de cerca
+const HIT_RANGE: f32 = 40.0;
+const ATTACK_WINDUP: f32 = 0.6;
+// Cada cuánto (segundos) puede recalcular un camino A*
+const REPLAN_INTERVAL: f32 = 0.5;
+// Distancia al centro de una celda del camino para darla por alcanzada
+const WAYPOINT_RADIUS: f32 = 6.0;
+// Deambular: radio (en celdas) del destino al azar y tiempo máximo para llegar
+const WANDER_RADIUS: i64 = 4;
+const WANDER_GIVE_UP: f32 = 12.0;
+// Investigar: tiempo máximo para llegar a la última posición conocida
+const INVESTIGATE_GIVE_UP: f32 = 10.0;
+// Mira alrededor antes de perder el interés
+const LOOK_AROUND_TIME: f32 = 3.0;
+const LOOK_AROUND_TURN: f32 = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZombieState {
+    Idle,
+    Wander,
+    Investigate,
+    Chase,
+    Attack,
+    LoseInterest,
+}
+
+impl ZombieState {
+    pub fn name(self) -> &'static str {
+        match self {
+            ZombieState::Idle => "idle",
+            ZombieState::Wander => "wander",
+            ZombieState::Investigate => "investigate",
+            ZombieState::Chase => "chase",
+            ZombieState::Attack => "attack",
+            ZombieState::LoseInterest => "lose interest",
+        }
+    }
+
+    // Multiplicador de la velocidad base en cada estado
+    pub fn speed_factor(self) -> f32 {
+        match self {
+            ZombieState::Idle | ZombieState::LoseInterest => 0.0,
+            ZombieState::Wander => 0.5,
+            ZombieState::Investigate => 0.85,
+            ZombieState::Chase => 1.25,
+            ZombieState::Attack => 0.6,
+        }
+    }
+
+    pub fn debug_color(self) -> Color {
+        match self {
+            ZombieState::Idle => Color::GRAY,
+            ZombieState::Wander => Color::SKYBLUE,
+            ZombieState::Investigate => Color::YELLOW,
+            ZombieState::Chase => Color::ORANGE,
+            ZombieState::Attack => Color::RED,
+            ZombieState::LoseInterest => Color::PURPLE,
+        }
+    }
+}
 
 pub struct Zombie {
     pub pos: Vector2,
+    pub archetype: String,
     pub speed: f32,
     pub radius: f32,
+    // Alto del sprite relativo a una pared
+    pub scale: f32,
+    // Radio en el que avisa a otros al ver al jugador (0 = no avisa)
+    pub alert_radius: f32,
+    pub sound: Option<String>,
+    // Dirección en la que mira (radianes), para elegir la vista del sprite
+    pub facing: f32,
+    pub anim: AnimatedSprite,
+    pub perception: Perception,
+    pub state: ZombieState,
+    // Tiempo que queda en el estado actual (su significado depende del estado)
+    state_timer: f32,
+    wander_goal: Option<Vector2>,
+    // Camino A* hacia un destino que no es el jugador a la vista, al revés:
+    // la próxima celda es la última
+    path: Vec<Cell>,
+    path_goal: Option<Cell>,
+    replan_timer: f32,
+    // Eventos del último update, para que main los atienda
+    pub spotted_player: bool,
+    pub alert: Option<Vector2>,
+}
+
+fn zombie_animation(sheet: char) -> AnimatedSprite {
+    AnimatedSprite::new(SpriteSheet::grid(sheet, 4, 2))
+        .with_clip("walk", Clip::new(vec![0, 1, 2, 3], 0.18, PlayMode::Loop))
+        .with_clip("attack", Clip::new(vec![4, 5], ATTACK_WINDUP / 2.0, PlayMode::Once))
 }
 
 impl Zombie {
     pub fn new(pos: Vector2) -> Self {
+        Self::from_archetype(pos, &Archetype::default())
+    }
+
+    pub fn from_archetype(pos: Vector2, arch: &Archetype) -> Self {
         Self {
             pos,
-            speed: 75.0,  
-            radius: 18.0, 
+            archetype: arch.name.clone(),
+            speed: arch.speed,
+            radius: arch.radius,
+            scale: arch.scale,
+            alert_radius: arch.alert_radius,
+            sound: arch.sound.clone(),
+            facing: 0.0,
+            anim: zombie_animation(arch.sprite),
+            perception: Perception::with_senses(arch.view_distance, arch.view_half_angle, arch.hearing),
+            state: ZombieState::Idle,
+            state_timer: 1.0,
+            wander_goal: None,
+            path: Vec::new(),
+            path_goal: None,
+            replan_timer: 0.0,
+            spotted_player: false,
+            alert: None,
+        }
+    }
+
+    pub fn sprite(&self) -> Sprite {
+        Sprite {
+            scale: self.scale,
+            facing: Some(self.facing),
+            ..Sprite::new(self.pos, self.anim.image())
+        }
+    }
+
+    fn enter(&mut self, state: ZombieState, timer: f32) {
+        self.state = state;
+        self.state_timer = timer;
+        self.path_goal = None;
+        self.path.clear();
+    }
+
+    /// Avanza la máquina de estados un frame. Devuelve `true` si un golpe
+    /// conectó con el jugador.
+    pub fn update(
+        &mut self,
+        maze: &Maze,
+        block_size: usize,
+        field: &NavField,
+        player: &Player,
+        dt: f32,
+        rng: &mut impl Rng,
+    ) -> bool {
+        self.perception.update(maze, block_size, self.pos, self.facing, player, dt);
+        let perceived = self.perception.sees_player || self.perception.hears_player;
+        let to_player = player.pos - self.pos;
+        let player_dist = (to_player.x * to_player.x + to_player.y * to_player.y).sqrt();
+        let start = self.pos;
+        let mut hit = false;
+        let calm = !matches!(self.state, ZombieState::Chase | ZombieState::Attack);
+        self.spotted_player = false;
+        self.alert = None;
+
+        self.state_timer -= dt;
+
+        // Percibir al jugador interrumpe cualquier estado salvo un golpe en curso
+        if perceived && self.state != ZombieState::Attack {
+            if calm {
+                self.spotted_player = true;
+                if self.alert_radius > 0.0 {
+                    self.alert = Some(player.pos);
+                }
+            }
+            if self.perception.sees_player && player_dist <= ATTACK_RANGE {
+                self.enter(ZombieState::Attack, ATTACK_WINDUP);
+            } else if self.state != ZombieState::Chase {
+                self.enter(ZombieState::Chase, 0.0);
+            }
+        }
+
+        match self.state {
+            ZombieState::Idle => {
+                if self.state_timer <= 0.0 {
+                    self.wander_goal = pick_wander_goal(maze, block_size, self.pos, rng);
+                    self.enter(ZombieState::Wander, WANDER_GIVE_UP);
+                }
+            }
+            ZombieState::Wander => {
+                let arrived = match self.wander_goal {
+                    Some(goal) => self.walk_path(maze, block_size, goal, dt),
+                    None => true,
+                };
+                if arrived || self.state_timer <= 0.0 {
+                    self.enter(ZombieState::Idle, rng.gen_range(1.0..3.0));
+                }
+            }
+            ZombieState::Chase => {
+                if perceived {
+                    // El campo compartido ya apunta a la celda del jugador
+                    let waypoint = next_waypoint(field, self.pos, block_size, player.pos);
+                    self.step_towards(maze, block_size, waypoint, dt);
+                } else {
+                    self.enter(ZombieState::Investigate, INVESTIGATE_GIVE_UP);
+                }
+            }
+            ZombieState::Investigate => {
+                let arrived = match self.perception.last_known {
+                    Some(goal) => self.walk_path(maze, block_size, goal, dt),
+                    None => true,
+                };
+                if arrived || self.state_timer <= 0.0 {
+                    self.enter(ZombieState::LoseInterest, LOOK_AROUND_TIME);
+                }
+            }
+            ZombieState::LoseInterest => {
+                self.facing += LOOK_AROUND_TURN * dt;
+                if self.state_timer <= 0.0 {
+                    self.perception.forget();
+                    self.enter(ZombieState::Idle, rng.gen_range(1.0..3.0));
+                }
+            }
+            ZombieState::Attack => {
+                // Se abalanza mientras prepara el golpe
+                self.facing = to_player.y.atan2(to_player.x);
+                self.step_towards(maze, block_size, player.pos, dt);
+                if self.state_timer <= 0.0 {
+                    let d = player.pos - self.pos;
+                    hit = self.perception.sees_player && d.x * d.x + d.y * d.y <= HIT_RANGE * HIT_RANGE;
+                    self.enter(ZombieState::Chase, 0.0);
+                }
+            }
+        }
+
+        self.animate(start, dt);
+        hit
+    }
+
+    /// Aviso de otro zombie: si no está persiguiendo a nadie, va a mirar.
+    pub fn hear_alert(&mut self, target: Vector2) {
+        if matches!(self.state, ZombieState::Idle | ZombieState::Wander | ZombieState::LoseInterest) {
+            self.perception.remember(target);
+            self.enter(ZombieState::Investigate, INVESTIGATE_GIVE_UP);
+        }
+    }
+
+    // Sigue un camino A* hasta `goal`, recalculándolo como mucho cada
+    // REPLAN_INTERVAL. Devuelve `true` al llegar.
+    fn walk_path(&mut self, maze: &Maze, block_size: usize, goal: Vector2, dt: f32) -> bool {
+        let d = goal - self.pos;
+        if d.x * d.x + d.y * d.y <= WAYPOINT_RADIUS * WAYPOINT_RADIUS {
+            return true;
+        }
+        let waypoint = self.path_waypoint(maze, block_size, goal, dt);
+        self.step_towards(maze, block_size, waypoint, dt);
+        false
+    }
+
+    fn path_waypoint(&mut self, maze: &Maze, block_size: usize, goal: Vector2, dt: f32) -> Vector2 {
+        let bs = block_size as f32;
+        let (Some(own), Some(goal_cell)) = (cell_at(self.pos, block_size), cell_at(goal, block_size)) else {
+            return goal;
+        };
+        if own == goal_cell { return goal; }
+
+        // Se recalcula si cambió el destino, si no hay camino (quizás ahora
+        // sí exista) o si lo empujaron fuera de él
+        self.replan_timer -= dt;
+        let off_path = match self.path.last() {
+            Some(&(i, j)) => own.0.abs_diff(i) + own.1.abs_diff(j) > 1,
+            None => true,
+        };
+        if self.replan_timer <= 0.0 && (self.path_goal != Some(goal_cell) || off_path) {
+            self.replan_timer = REPLAN_INTERVAL;
+            self.path_goal = Some(goal_cell);
+            self.path = find_path(maze, own, goal_cell).unwrap_or_default();
+            self.path.reverse();
+        }
+
+        while let Some(&(i, j)) = self.path.last() {
+            let center = Vector2::new((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs);
+            let d = center - self.pos;
+            if d.x * d.x + d.y * d.y > WAYPOINT_RADIUS * WAYPOINT_RADIUS {
+                return center;
+            }
+            self.path.pop();
+        }
+        goal
+    }
+
+    // Camina al ritmo de su desplazamiento real; en Attack juega el golpe
+    fn animate(&mut self, start: Vector2, dt: f32) {
+        if self.state == ZombieState::Attack {
+            self.anim.play("attack");
+            self.anim.speed = 1.0;
+        } else {
+            self.anim.play("walk");
+            let moved = self.pos - start;
+            let moved_len = (moved.x * moved.x + moved.y * moved.y).sqrt();
+            if moved_len > 0.001 {
+                self.facing = moved.y.atan2(moved.x);
+            }
+            let actual_speed = moved_len / dt.max(0.0001);
+            self.anim.speed = actual_speed / self.speed;
         }
+        self.anim.update(dt);
     }
 
-    pub fn update(&mut self, maze: &Vec<Vec<char>>, block_size: usize, target: Vector2, dt: f32) {
+    fn step_towards(&mut self, maze: &[Vec<char>], block_size: usize, target: Vector2, dt: f32) {
         let mut dir = target - self.pos;
         let len = (dir.x * dir.x + dir.y * dir.y).sqrt();
         if len > 0.001 {
@@ -26,7 +324,8 @@ impl Zombie {
             return;
         }
 
-        let step = Vector2::new(dir.x * self.speed * dt, dir.y * self.speed * dt);
+        let speed = self.speed * self.state.speed_factor();
+        let step = Vector2::new(dir.x * speed * dt, dir.y * speed * dt);
         let candidate = Vector2::new(self.pos.x + step.x, self.pos.y + step.y);
 
         if is_walkable(maze, candidate, block_size) {
@@ -43,15 +342,237 @@ impl Zombie {
     }
 }
 
-fn is_walkable(maze: &Vec<Vec<char>>, p: Vector2, block_size: usize) -> bool {
+// Centro de la celda vecina que indica el campo; en la celda del jugador
+// (o fuera del campo) va directo al objetivo
+fn next_waypoint(field: &NavField, pos: Vector2, block_size: usize, target: Vector2) -> Vector2 {
+    let bs = block_size as f32;
+    match cell_at(pos, block_size).and_then(|cell| field.next_toward(cell)) {
+        Some((i, j)) => Vector2::new((i as f32 + 0.5) * bs, (j as f32 + 0.5) * bs),
+        None => target,
+    }
+}
+
+/// Reparte los avisos de este frame (screamers que acaban de ver al jugador)
+/// a los zombies dentro de su radio.
+pub fn propagate_alerts(zombies: &mut [Zombie]) {
+    let alerts: Vec<(usize, Vector2, f32, Vector2)> = zombies.iter().enumerate()
+        .filter_map(|(idx, z)| z.alert.map(|target| (idx, z.pos, z.alert_radius, target)))
+        .collect();
+    for (from, origin, radius, target) in alerts {
+        for (idx, z) in zombies.iter_mut().enumerate() {
+            let d = z.pos - origin;
+            if idx != from && d.x * d.x + d.y * d.y <= radius * radius {
+                z.hear_alert(target);
+            }
+        }
+    }
+}
+
+/// Separa zombies que se solapan (círculo contra círculo). Los agrupa por
+/// celda del laberinto, así cada uno solo se compara con los de su celda y
+/// las 8 vecinas. Cada uno cede la mitad del solapamiento, sin entrar en muros.
+pub fn resolve_overlaps(zombies: &mut [Zombie], maze: &Maze, block_size: usize) {
+    let mut buckets: HashMap<Cell, Vec<usize>> = HashMap::new();
+    for (idx, z) in zombies.iter().enumerate() {
+        if let Some(cell) = cell_at(z.pos, block_size) {
+            buckets.entry(cell).or_default().push(idx);
+        }
+    }
+
+    for a in 0..zombies.len() {
+        let Some((ci, cj)) = cell_at(zombies[a].pos, block_size) else { continue; };
+        for nj in cj.saturating_sub(1)..=cj + 1 {
+            for ni in ci.saturating_sub(1)..=ci + 1 {
+                let Some(bucket) = buckets.get(&(ni, nj)) else { continue; };
+                for &b in bucket {
+                    // Cada par una sola vez
+                    if b <= a { continue; }
+                    push_apart(zombies, a, b, maze, block_size);
+                }
+            }
+        }
+    }
+}
+
+fn push_apart(zombies: &mut [Zombie], a: usize, b: usize, maze: &Maze, block_size: usize) {
+    let delta = zombies[b].pos - zombies[a].pos;
+    let min_dist = zombies[a].radius + zombies[b].radius;
+    let dist2 = delta.x * delta.x + delta.y * delta.y;
+    if dist2 >= min_dist * min_dist { return; }
+
+    let dist = dist2.sqrt();
+    // Encimados exactamente: los separa en una dirección fija según el índice
+    let normal = if dist > 0.001 {
+        Vector2::new(delta.x / dist, delta.y / dist)
+    } else {
+        let ang = b as f32 * 2.399;
+        Vector2::new(ang.cos(), ang.sin())
+    };
+    let push = (min_dist - dist) * 0.5;
+
+    let new_a = zombies[a].pos - normal * push;
+    let new_b = zombies[b].pos + normal * push;
+    if is_walkable(maze, new_a, block_size) { zombies[a].pos = new_a; }
+    if is_walkable(maze, new_b, block_size) { zombies[b].pos = new_b; }
+}
+
+// Celda caminable al azar a pocas celdas de distancia
+fn pick_wander_goal(maze: &Maze, block_size: usize, pos: Vector2, rng: &mut impl Rng) -> Option<Vector2> {
+    let (i, j) = cell_at(pos, block_size)?;
+    let bs = block_size as f32;
+    for _ in 0..10 {
+        let ci = i as i64 + rng.gen_range(-WANDER_RADIUS..=WANDER_RADIUS);
+        let cj = j as i64 + rng.gen_range(-WANDER_RADIUS..=WANDER_RADIUS);
+        if ci < 0 || cj < 0 { continue; }
+        let cell = (ci as usize, cj as usize);
+        if cell != (i, j) && is_walkable_cell(maze, cell) {
+            return Some(Vector2::new((ci as f32 + 0.5) * bs, (cj as f32 + 0.5) * bs));
+        }
+    }
+    None
+}
+
+fn is_walkable(maze: &[Vec<char>], p: Vector2, block_size: usize) -> bool {
     if p.x < 0.0 || p.y < 0.0 { return false; }
     let i = (p.x / block_size as f32) as isize;
     let j = (p.y / block_size as f32) as isize;
     if i < 0 || j < 0 { return false; }
-    let (i, j) = (i as usize, j as usize);
-    if j >= maze.len() || i >= maze[0].len() { return false; }
-    match maze[j][i] {
-        ' ' | 'M' | 'g' => true, 
-        _ => false,              
+    is_walkable_cell(maze, (i as usize, j as usize))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const BS: usize = 80;
+
+    fn corridor() -> Vec<Vec<char>> {
+        ["#########", "#       #", "#########"].iter().map(|r| r.chars().collect()).collect()
+    }
+
+    fn player_at(x: f32) -> Player {
+        Player::new(Vector2::new(x, 120.0), std::f32::consts::PI, std::f32::consts::PI / 3.0)
+    }
+
+    #[test]
+    fn overlapping_zombies_are_pushed_apart() {
+        let maze = corridor();
+        let mut zombies: Vec<Zombie> = (0..3).map(|_| Zombie::new(Vector2::new(300.0, 120.0))).collect();
+        for _ in 0..20 {
+            resolve_overlaps(&mut zombies, &maze, BS);
+        }
+        for a in 0..zombies.len() {
+            assert!(is_walkable(&maze, zombies[a].pos, BS));
+            for b in a + 1..zombies.len() {
+                let d = zombies[b].pos - zombies[a].pos;
+                let min_dist = zombies[a].radius + zombies[b].radius;
+                assert!(d.x * d.x + d.y * d.y >= (min_dist * 0.95).powi(2));
+            }
+        }
+    }
+
+    #[test]
+    fn replans_when_pushed_off_the_path_or_the_goal_opens_up() {
+        // Dos pasillos unidos por la columna 7; la meta queda en el de abajo
+        let mut maze: Vec<Vec<char>> = ["#########", "#       #", "####### #", "#       #", "#########"]
+            .iter().map(|r| r.chars().collect()).collect();
+        let cell_center = |(i, j): Cell| Vector2::new((i as f32 + 0.5) * BS as f32, (j as f32 + 0.5) * BS as f32);
+        let goal = cell_center((1, 3));
+
+        let mut zombie = Zombie::new(cell_center((1, 1)));
+        assert_eq!(zombie.path_waypoint(&maze, BS, goal, 0.1), cell_center((2, 1)));
+
+        // Empujado lejos de su próxima celda: al cumplirse el intervalo rehace el camino
+        zombie.pos = cell_center((6, 1));
+        assert_eq!(zombie.path_waypoint(&maze, BS, goal, 0.1), cell_center((2, 1)));
+        assert_eq!(zombie.path_waypoint(&maze, BS, goal, REPLAN_INTERVAL), cell_center((7, 1)));
+
+        // Sin camino no se queda con la lista vacía para siempre
+        maze[2][7] = '#';
+        zombie.pos = cell_center((1, 1));
+        zombie.replan_timer = 0.0;
+        zombie.path_goal = None;
+        assert_eq!(zombie.path_waypoint(&maze, BS, goal, 0.1), goal);
+        maze[2][7] = ' ';
+        assert_eq!(zombie.path_waypoint(&maze, BS, goal, REPLAN_INTERVAL), cell_center((2, 1)));
+    }
+
+    #[test]
+    fn attack_only_lands_after_the_windup() {
+        let maze = corridor();
+        let player = player_at(150.0);
+        let mut field = NavField::new();
+        field.update(&maze, (1, 1));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let mut zombie = Zombie::new(Vector2::new(200.0, 120.0));
+        zombie.facing = std::f32::consts::PI;
+
+        assert!(!zombie.update(&maze, BS, &field, &player, 0.1, &mut rng));
+        assert_eq!(zombie.state, ZombieState::Attack);
+
+        let mut hit = false;
+        for _ in 0..10 {
+            hit |= zombie.update(&maze, BS, &field, &player, 0.1, &mut rng);
+        }
+        assert!(hit);
+    }
+
+    #[test]
+    fn loses_interest_after_investigating() {
+        let maze = corridor();
+        let mut field = NavField::new();
+        field.update(&maze, (7, 1));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        // Oye al jugador corriendo y después el jugador se queda quieto fuera de vista
+        let mut zombie = Zombie::new(Vector2::new(120.0, 120.0));
+        let mut player = player_at(600.0);
+        player.noise = 600.0;
+        zombie.update(&maze, BS, &field, &player, 0.1, &mut rng);
+        assert_eq!(zombie.state, ZombieState::Chase);
+
+        player.noise = 0.0;
+        player.pos = Vector2::new(600.0, 120.0);
+        zombie.facing = std::f32::consts::PI;
+        zombie.update(&maze, BS, &field, &player, 0.1, &mut rng);
+        assert_eq!(zombie.state, ZombieState::Investigate);
+
+        let mut seen = vec![zombie.state];
+        for _ in 0..200 {
+            // Nunca lo mira: el jugador queda a sus espaldas
+            zombie.facing = std::f32::consts::PI;
+            zombie.update(&maze, BS, &field, &player_at(-1000.0), 0.1, &mut rng);
+            if seen.last() != Some(&zombie.state) { seen.push(zombie.state); }
+        }
+        assert_eq!(&seen[..3], &[ZombieState::Investigate, ZombieState::LoseInterest, ZombieState::Idle]);
+        assert_eq!(zombie.perception.last_known, None);
+    }
+
+    #[test]
+    fn screamer_sends_nearby_zombies_to_investigate() {
+        let maze = corridor();
+        let mut field = NavField::new();
+        field.update(&maze, (7, 1));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        let screamer = Archetype { alert_radius: 300.0, ..Archetype::default() };
+        let mut zombies = vec![
+            Zombie::from_archetype(Vector2::new(300.0, 120.0), &screamer),
+            Zombie::new(Vector2::new(120.0, 120.0)),
+            Zombie::new(Vector2::new(680.0, 120.0)),
+        ];
+        let player = player_at(600.0);
+        zombies[0].update(&maze, BS, &field, &player, 0.1, &mut rng);
+        assert!(zombies[0].spotted_player);
+        assert_eq!(zombies[0].alert, Some(player.pos));
+
+        propagate_alerts(&mut zombies);
+        assert_eq!(zombies[1].state, ZombieState::Investigate);
+        assert_eq!(zombies[1].perception.last_known, Some(player.pos));
+        // Fuera del radio no se entera
+        assert_eq!(zombies[2].state, ZombieState::Idle);
     }
 }